@@ -1,3 +1,8 @@
+use device::device::codec::{Compression, Encoding, WireFormat};
+use device::device::file_sys::SymlinkPolicy;
+use device::device::label::DeviceLabel;
+use device::device::render::TreeRenderer;
+use device::device::share_policy::SharePolicies;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -11,8 +16,55 @@ pub struct ServerConfig {
     pub master_ip: String,
     pub master_port: u16,
 }
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct ClientConfig {
     pub file_storage: String,
     pub listen_port: u16,
+    #[serde(default)]
+    pub ignore: Vec<String>, // 공유하지 않을 entry의 gitignore 형식 pattern
+    #[serde(default)]
+    pub symlink_policy: SymlinkPolicy,
+    #[serde(default)]
+    pub scan_depth: Option<usize>, // master에 처음 등록할 tree의 최대 깊이, 없으면 전체
+    #[serde(default)]
+    pub encoding: Encoding, // master에 tree를 올릴 때의 직렬화 형식
+    #[serde(default)]
+    pub compression: Compression,
+    #[serde(default)]
+    pub tree: TreeRenderer, // FileSystem 메뉴에서 tree를 출력하는 방식
+    #[serde(default)]
+    pub share: SharePolicies, // root 기준 상대경로별 공개 범위, 없으면 모두 전송 가능
+    #[serde(default)]
+    pub label: String, // group에서 이 device를 가리키는 이름
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub advertise_addrs: Vec<String>, // 다른 device가 가장 먼저 시도할 주소, "host" 혹은 "host:port"
+}
+
+// 실행 중에 변경한 label과 tag를 다음 실행에도 사용하도록 config.toml 옆에 저장
+pub const DEVICE_LABEL_FILE: &str = "device_label.toml";
+
+impl ClientConfig {
+    pub fn wire_format(&self) -> WireFormat {
+        WireFormat::new(self.encoding, self.compression)
+    }
+
+    // 저장된 파일이 있으면 config.toml의 값보다 우선
+    pub fn device_label(&self) -> DeviceLabel {
+        let saved = std::fs::read_to_string(DEVICE_LABEL_FILE)
+            .ok()
+            .and_then(|saved| toml::from_str(&saved).ok());
+
+        saved.unwrap_or_else(|| DeviceLabel {
+            label: self.label.clone(),
+            tags: self.tags.clone(),
+        })
+    }
+}
+
+pub fn save_device_label(device_label: &DeviceLabel) -> std::io::Result<()> {
+    let serialized = toml::to_string(device_label)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(DEVICE_LABEL_FILE, serialized)
 }
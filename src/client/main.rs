@@ -5,6 +5,10 @@ mod network;
 mod ui;
 
 use config::{ClientConfig, Config, ServerConfig};
+use device::device::codec::{Compression, Encoding};
+use device::device::file_sys::SymlinkPolicy;
+use device::device::render::TreeRenderer;
+use device::device::share_policy::SharePolicies;
 use ui::cli::interface::Cli;
 use ui::gui::interface::Gui;
 use ui::interface::Interface;
@@ -29,6 +33,16 @@ async fn main() {
                     // TODO: os별 다른 기본 file_storage
                     file_storage: String::from("/tmp"),
                     listen_port: 8081,
+                    ignore: Vec::new(),
+                    symlink_policy: SymlinkPolicy::default(),
+                    scan_depth: None,
+                    encoding: Encoding::default(),
+                    compression: Compression::default(),
+                    tree: TreeRenderer::default(),
+                    share: SharePolicies::default(),
+                    label: String::new(),
+                    tags: Vec::new(),
+                    advertise_addrs: Vec::new(),
                 },
             }
         }
@@ -46,19 +60,11 @@ async fn main() {
     // TODO: ui 옵션 파싱 방식 변경
     if env::args().nth(1).unwrap() == "cli" {
         println!("CLI mode로 시작합니다.");
-        let mut cli: Cli = Interface::new(
-            master_addr,
-            config_content.client.listen_port,
-            config_content.client.file_storage.to_string(),
-        );
+        let mut cli: Cli = Interface::new(master_addr, config_content.client.clone());
         cli.entry().await;
     } else if env::args().nth(1).unwrap() == "gui" {
         println!("GUI mode로 시작합니다.");
-        let mut gui = Gui::new(
-            master_addr,
-            config_content.client.listen_port,
-            config_content.client.file_storage.to_string(),
-        );
+        let mut gui = Gui::new(master_addr, config_content.client.clone());
         gui.entry().await;
     } else {
         println!("usage: ./client [cli | gui]");
@@ -1,21 +1,62 @@
 use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use device::device::endpoint::{self, Endpoint};
+use device::device::transfer::{self, ServeError};
+use device::device::{file_sys::FileSystem, hash};
+
+// 사용할 수 없는 후보(다른 network의 사설 주소 등)에서 오래 기다리지 않도록 제한
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+// 요청을 끝까지 보내지 않는 peer가 listener를 막지 않도록 제한
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Clone)]
 pub struct TcpNetwork {
     // file transfer 요청을 받는 binding된 port
     pub listen_port: u16,
     file_storage: String,
+    device_fs: Arc<Mutex<Option<FileSystem>>>, // group에 공유 중인 file system, 등록 전에는 None
+    bytes_served: Arc<AtomicU64>,              // 다른 device에 전송한 파일의 byte 수 (telemetry)
 }
 
 // TODO: unwrap 처리
 impl TcpNetwork {
-    pub fn new(listen_port: u16, file_storage: String) -> Self {
+    pub fn new(
+        listen_port: u16,
+        file_storage: String,
+        device_fs: Arc<Mutex<Option<FileSystem>>>,
+    ) -> Self {
         TcpNetwork {
             listen_port,
             file_storage,
+            device_fs,
+            bytes_served: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn bytes_served(&self) -> u64 {
+        self.bytes_served.load(Ordering::Relaxed)
+    }
+
+    // 요청은 공유 중인 tree의 root 기준 상대경로이며, tree에 있는 파일 중 share policy가 downloadable인 것만 전송
+    fn resolve_servable(&self, requested_path: &str) -> Result<PathBuf, ServeError> {
+        let device_fs_lock = self.device_fs.lock().unwrap();
+        match device_fs_lock.as_ref() {
+            Some(device_fs) => device_fs.resolve_request(requested_path),
+            None => Err(ServeError::NotShared),
         }
     }
+
+    fn read_servable(&self, requested_path: &str) -> Result<Vec<u8>, ServeError> {
+        let requested_path = self.resolve_servable(requested_path)?;
+
+        std::fs::read(requested_path).map_err(|e| ServeError::Io(e.to_string()))
+    }
+
     pub fn listener_init(&self) -> TcpListener {
         let addr = format!("{}:{}", "0.0.0.0".to_string(), self.listen_port);
         let listener = TcpListener::bind(addr).expect("이미 사용중인 포트입니다.");
@@ -23,19 +64,112 @@ impl TcpNetwork {
         listener
     }
 
-    pub fn connect(&self, peer_addr: String) -> Result<TcpStream, std::io::Error> {
-        // file transfer 요청을 보내기 위한 연결
-        TcpStream::connect(peer_addr)
+    // file transfer 요청을 보내기 위한 연결
+    // 후보를 순서대로 시도해서 처음 연결된 주소를 사용, 모두 실패하면 마지막 오류를 반환
+    // hostname 후보는 이 device에서 resolve
+    pub fn connect_any(
+        &self,
+        candidates: &[Endpoint],
+    ) -> Result<(TcpStream, SocketAddr), std::io::Error> {
+        let mut last_error = std::io::Error::new(
+            std::io::ErrorKind::AddrNotAvailable,
+            "연결할 수 있는 주소가 없습니다.",
+        );
+
+        for candidate in candidates {
+            let addrs = match candidate.resolve() {
+                Ok(addrs) => addrs,
+                Err(e) => {
+                    log::debug!("{}의 주소를 확인하지 못했습니다: {}", candidate, e);
+                    last_error = e;
+                    continue;
+                }
+            };
+
+            for addr in addrs {
+                match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+                    Ok(stream) => return Ok((stream, addr)),
+                    Err(e) => {
+                        log::debug!("{}({})에 연결하지 못했습니다: {}", candidate, addr, e);
+                        last_error = e;
+                    }
+                }
+            }
+        }
+
+        Err(last_error)
     }
 
-    pub fn send_request(&self, tcp_stream: &mut TcpStream, file_name: String) {
-        tcp_stream.write_all(file_name.as_bytes()).unwrap();
-        tcp_stream.flush().unwrap();
+    // 다른 device가 연결을 시도할 이 device의 주소 후보
+    // config에 지정한 주소("host", "host:port")와 network interface의 주소
+    pub fn local_endpoints(&self, advertise_addrs: &[String]) -> Vec<Endpoint> {
+        let mut endpoints = Vec::new();
 
-        let mut buf = [0u8; 1024];
-        let sz = tcp_stream.read(&mut buf).unwrap();
+        // hostname은 연결하는 peer가 resolve하도록 그대로 전달
+        for advertise_addr in advertise_addrs {
+            match Endpoint::advertised(advertise_addr, self.listen_port) {
+                Some(endpoint) => endpoints.push(endpoint),
+                None => log::warn!("광고할 주소의 형식이 잘못되었습니다: {}", advertise_addr),
+            }
+        }
+
+        match if_addrs::get_if_addrs() {
+            Ok(interfaces) => {
+                for interface in interfaces {
+                    let ip = interface.ip();
+                    if let Some(kind) = endpoint::classify(&ip) {
+                        endpoints.push(Endpoint::new(SocketAddr::new(ip, self.listen_port), kind));
+                    }
+                }
+            }
+            Err(e) => log::warn!("network interface 목록을 읽지 못했습니다: {}", e),
+        }
 
-        self.save_file(&buf[..sz], file_name);
+        endpoints
+    }
+
+    pub fn send_request(
+        &self,
+        tcp_stream: &mut TcpStream,
+        file_name: String,
+        expected_hash: Option<&str>,
+    ) {
+        // 요청을 보낸 뒤 쓰기를 종료하고, peer는 응답 후 연결을 종료하므로 EOF까지 읽음
+        // 이미 응답하고 연결을 종료한 peer에 대한 shutdown 실패는 무시
+        let mut reply = Vec::new();
+        let exchanged = tcp_stream
+            .write_all(&transfer::request_line(&file_name))
+            .and_then(|_| tcp_stream.flush())
+            .and_then(|_| match tcp_stream.shutdown(Shutdown::Write) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotConnected => Ok(()),
+                result => result,
+            })
+            .and_then(|_| tcp_stream.read_to_end(&mut reply));
+        if let Err(e) = exchanged {
+            println!("Device와 파일을 주고받지 못했습니다: {}", e);
+            return;
+        }
+
+        let buf = match transfer::parse_reply(&reply) {
+            Ok(buf) => buf,
+            Err(reason) => {
+                println!("Device가 파일 전송을 거부했습니다: {}", reason);
+                return;
+            }
+        };
+
+        if let Some(expected_hash) = expected_hash {
+            let received_hash = hash::hash_bytes(buf);
+            if received_hash != expected_hash {
+                println!(
+                    "전송받은 파일의 hash가 일치하지 않습니다. 저장하지 않습니다: {} != {}",
+                    received_hash, expected_hash
+                );
+                return;
+            }
+        }
+
+        self.save_file(buf, file_name);
     }
 
     fn save_file(&self, buffer: &[u8], file_name: String) {
@@ -56,26 +190,46 @@ impl TcpNetwork {
             }
         };
 
-        created_file.write(payload_bytes).unwrap();
-        created_file.flush().unwrap();
+        if let Err(e) = created_file
+            .write_all(payload_bytes)
+            .and_then(|_| created_file.flush())
+        {
+            println!("파일을 저장하지 못했습니다: {} ({})", abs_file_path, e);
+        }
     }
 
     pub fn listen(&self, listener: TcpListener) {
         for stream in listener.incoming() {
             match stream {
                 Ok(mut stream) => {
-                    let mut buf = [0u8; 1024];
-                    let peer_device_request = stream.read(&mut buf).unwrap();
-                    let peer_device_request_str =
-                        std::str::from_utf8(&buf[..peer_device_request]).unwrap();
-
-                    let mut requested_file = std::fs::File::open(peer_device_request_str).unwrap();
+                    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+                    let peer_device_request = match transfer::read_request(&mut stream) {
+                        Ok(peer_device_request) => peer_device_request,
+                        Err(e) => {
+                            let _ = stream.write_all(&transfer::err_reply(&e));
+                            continue;
+                        }
+                    };
 
-                    let mut file_bytes = vec![0u8];
-                    let data = requested_file.read_to_end(&mut file_bytes).unwrap();
+                    let reply = match self.read_servable(&peer_device_request) {
+                        Ok(file_bytes) => {
+                            self.bytes_served
+                                .fetch_add(file_bytes.len() as u64, Ordering::Relaxed);
+                            let mut reply = transfer::ok_reply_header().to_vec();
+                            reply.extend_from_slice(&file_bytes);
+                            reply
+                        }
+                        Err(e) => {
+                            println!(
+                                "파일 전송 요청을 거부했습니다({}): {}",
+                                peer_device_request, e
+                            );
+                            transfer::err_reply(&e)
+                        }
+                    };
 
-                    stream.write(&file_bytes[..data]).unwrap();
-                    stream.flush().unwrap();
+                    let _ = stream.write_all(&reply);
+                    let _ = stream.flush();
                 }
                 Err(e) => {
                     eprintln!(
@@ -2,17 +2,25 @@
 pub enum ActionNum {
     DeviceList = 0,
     FileSystem,
+    FileChanges,
     FileTransfer,
+    FileSearch,
+    Namespace,
+    DeviceLabel,
     Exit,
     Undefined,
 }
 
 impl ActionNum {
     pub fn iter() -> std::slice::Iter<'static, ActionNum> {
-        static ACTIONS: [ActionNum; 4] = [
+        static ACTIONS: [ActionNum; 8] = [
             ActionNum::DeviceList,
             ActionNum::FileSystem,
+            ActionNum::FileChanges,
             ActionNum::FileTransfer,
+            ActionNum::FileSearch,
+            ActionNum::Namespace,
+            ActionNum::DeviceLabel,
             ActionNum::Exit,
         ];
         ACTIONS.iter()
@@ -24,7 +32,11 @@ impl std::fmt::Display for ActionNum {
         match *self {
             ActionNum::DeviceList => write!(f, "DeviceList"),
             ActionNum::FileSystem => write!(f, "FileSystem"),
+            ActionNum::FileChanges => write!(f, "FileChanges"),
             ActionNum::FileTransfer => write!(f, "FileTransfer"),
+            ActionNum::FileSearch => write!(f, "FileSearch"),
+            ActionNum::Namespace => write!(f, "Namespace"),
+            ActionNum::DeviceLabel => write!(f, "DeviceLabel"),
             ActionNum::Exit => write!(f, "Exit"),
             ActionNum::Undefined => write!(f, "Undefined"),
         }
@@ -38,8 +50,12 @@ impl TryFrom<i32> for ActionNum {
         match value {
             0 => Ok(ActionNum::DeviceList),
             1 => Ok(ActionNum::FileSystem),
-            2 => Ok(ActionNum::FileTransfer),
-            3 => Ok(ActionNum::Exit),
+            2 => Ok(ActionNum::FileChanges),
+            3 => Ok(ActionNum::FileTransfer),
+            4 => Ok(ActionNum::FileSearch),
+            5 => Ok(ActionNum::Namespace),
+            6 => Ok(ActionNum::DeviceLabel),
+            7 => Ok(ActionNum::Exit),
             _ => Ok(ActionNum::Undefined),
         }
     }
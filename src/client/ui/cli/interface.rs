@@ -1,13 +1,13 @@
 use colored::Colorize;
 use iced::futures::{self, pin_mut, SinkExt, StreamExt};
 use reqwest::Url;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
 use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
-use std::{
-    borrow::BorrowMut,
-    io::{self, Write},
-};
-use sysinfo::{System, SystemExt};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{DiskExt, System, SystemExt};
 use tokio_tungstenite::tungstenite;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use uuid::Uuid;
@@ -15,9 +15,26 @@ use uuid::Uuid;
 use super::super::interface;
 use super::super::request;
 use super::action;
+use crate::config::{self, ClientConfig};
 use crate::network::tcp::network::TcpNetwork;
 use crate::ui::request::DeviceManager;
-use device::device::{file_sys::FileSystem, spec::DeviceSpec};
+use device::device::{
+    codec::WireFormat,
+    file_sys::{format_size, FileKind, FileSystem, FsError, ScanProgress},
+    label::{self, DeviceLabel},
+    message::WsCommand,
+    namespace::{self, NamespaceView},
+    protocol::Capabilities,
+    query::node_id,
+    spec::{self, DeviceSpec},
+    telemetry::Telemetry,
+    watcher::FsWatcher,
+};
+
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+const SCAN_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+const UNREADABLE_PATHS_SHOWN: usize = 10;
+const TELEMETRY_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Clone)]
 pub struct Cli {
@@ -25,6 +42,9 @@ pub struct Cli {
     device_manager_uuid: Uuid,
     device_uuid: Uuid,
     network: TcpNetwork,
+    client_config: ClientConfig,
+    device_fs: Arc<Mutex<Option<FileSystem>>>, // group에 공유 중인 local file system
+    last_seen_fs: Arc<Mutex<BTreeMap<Uuid, FileSystem>>>, // device별 마지막으로 확인한 file system
 }
 
 impl Cli {
@@ -44,24 +64,298 @@ impl Cli {
         // TODO: websocket을 통해 전달받은 device:uuid 에 해당하는 spec과 fs 업데이트
         let master_addr_clone = self.master_addr.clone();
         let device_manager_uuid_clone = self.device_manager_uuid.clone();
+        let device_uuid_clone = self.device_uuid.clone();
+        let device_fs_clone = Arc::clone(&self.device_fs);
+        let wire_format = self.client_config.wire_format();
 
         tokio::spawn(async move {
             tokio::pin!(read);
 
             while let Some(msg) = read.next().await {
                 match msg {
-                    Ok(Message::Text(_)) => {
-                        let _d = request::get_device_manager(
-                            &master_addr_clone,
-                            device_manager_uuid_clone,
+                    Ok(Message::Text(text)) => match WsCommand::from_text(&text) {
+                        WsCommand::Refresh => {
+                            let _d = match request::get_device_manager(
+                                &master_addr_clone,
+                                device_manager_uuid_clone,
+                            )
+                            .await
+                            {
+                                Ok(_d) => _d,
+                                Err(e) => {
+                                    log::warn!("group 정보를 갱신하지 못했습니다: {}", e);
+                                    continue;
+                                }
+                            };
+
+                            let mut device_manager_lock = device_manager.lock().unwrap();
+                            let _ = std::mem::replace(&mut *device_manager_lock, _d);
+                        }
+                        WsCommand::ExpandSubtree { path } => {
+                            Cli::expand_device_fs(
+                                &master_addr_clone,
+                                device_manager_uuid_clone,
+                                device_uuid_clone,
+                                Arc::clone(&device_fs_clone),
+                                path,
+                                wire_format,
+                            )
+                            .await;
+                        }
+                        WsCommand::Telemetry { .. } => {}
+                    },
+                    _ => {}
+                }
+            }
+        });
+    }
+
+    // group에 참가하기 전에 master와 protocol version을 맞추고, master가 읽을 수 있는 형식으로 전송하도록 설정
+    async fn handshake(&mut self) {
+        let master_capabilities =
+            match request::post_handshake(&self.master_addr, &Capabilities::current()).await {
+                Ok(master_capabilities) => master_capabilities,
+                Err(e) => {
+                    println!("master에 접속할 수 없습니다. {}", e);
+                    process::exit(-1);
+                }
+            };
+
+        let preferred = self.client_config.wire_format();
+        let wire_format =
+            Capabilities::current().choose_wire_format(preferred, &master_capabilities);
+        if wire_format != preferred {
+            println!(
+                "master가 지원하지 않는 형식이므로 {:?}/{:?} 대신 {:?}/{:?}로 전송합니다.",
+                preferred.encoding,
+                preferred.compression,
+                wire_format.encoding,
+                wire_format.compression
+            );
+        }
+        self.client_config.encoding = wire_format.encoding;
+        self.client_config.compression = wire_format.compression;
+    }
+
+    // 공유 중인 root의 여유 공간, 부하, 전송량 등을 주기적으로 master에 전달
+    fn report_telemetry(&self, mut write: impl futures::Sink<Message> + Unpin + Send + 'static) {
+        let device_fs_clone = Arc::clone(&self.device_fs);
+        let network_clone = self.network.clone();
+        let started_at = Instant::now();
+
+        tokio::spawn(async move {
+            let mut system = System::new();
+            system.refresh_disks_list();
+
+            loop {
+                system.refresh_disks();
+                let volumes: Vec<_> = system
+                    .disks()
+                    .iter()
+                    .map(|disk| (disk.mount_point().to_path_buf(), disk.available_space()))
+                    .collect();
+                let free_disk = device_fs_clone
+                    .lock()
+                    .unwrap()
+                    .as_ref()
+                    .and_then(|device_fs| {
+                        spec::volume_free_space(
+                            &volumes,
+                            std::path::Path::new(device_fs.node.file_name()),
                         )
-                        .await
-                        .unwrap();
+                    });
+                let load_average = match std::env::consts::OS {
+                    "windows" => None,
+                    _ => Some(system.load_average().one),
+                };
+
+                let sample = Telemetry {
+                    timestamp: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map(|duration| duration.as_secs())
+                        .unwrap_or(0),
+                    free_disk,
+                    load_average,
+                    bytes_served: network_clone.bytes_served(),
+                    uptime: started_at.elapsed().as_secs(),
+                };
+
+                let text = WsCommand::Telemetry { sample }.to_text();
+                if write.send(Message::Text(text)).await.is_err() {
+                    log::warn!("telemetry를 전달하지 못했습니다. 전송을 중단합니다.");
+                    break;
+                }
+                tokio::time::sleep(TELEMETRY_INTERVAL).await;
+            }
+        });
+    }
+
+    // 다른 device가 탐색하지 않은 directory를 열었을 때 master의 요청으로 실행
+    async fn expand_device_fs(
+        master_addr: &str,
+        manager_uuid: Uuid,
+        device_uuid: Uuid,
+        device_fs: Arc<Mutex<Option<FileSystem>>>,
+        subtree_path: String,
+        wire_format: WireFormat,
+    ) {
+        let update = tokio::task::spawn_blocking(move || {
+            let mut device_fs_lock = device_fs.lock().unwrap();
+            device_fs_lock.as_mut().and_then(|device_fs| {
+                let update = device_fs.expand_subtree(std::path::Path::new(&subtree_path))?;
+                device_fs.publish_update(update)
+            })
+        })
+        .await
+        .unwrap_or(None);
+
+        let update = match update {
+            Some(update) => update,
+            None => {
+                println!("요청받은 directory를 탐색하지 못했습니다.");
+                return;
+            }
+        };
+
+        if let Err(e) = request::patch_device_fs_subtree(
+            master_addr,
+            manager_uuid,
+            device_uuid,
+            update,
+            wire_format,
+        )
+        .await
+        {
+            println!("탐색한 directory를 master에 반영하지 못했습니다: {}", e);
+        }
+    }
+
+    // 탐색 중에는 Ctrl+C로 탐색만 취소하고, 탐색이 끝나면 원래대로 프로그램을 종료
+    // 읽지 못한 경로는 알리고 제외한 tree를 사용
+    async fn scan_device_fs(mut device_fs: FileSystem) -> Result<FileSystem, FsError> {
+        let progress = Arc::new(ScanProgress::new());
+        let is_scan_finished = Arc::new(AtomicBool::new(false));
+        let _ = signal_hook::flag::register_conditional_default(
+            signal_hook::consts::SIGINT,
+            Arc::clone(&is_scan_finished),
+        );
+        let _ = signal_hook::flag::register(signal_hook::consts::SIGINT, progress.cancel_flag());
+
+        let progress_clone = Arc::clone(&progress);
+        let scan_task = tokio::task::spawn_blocking(move || {
+            let scan_result = device_fs.init_file_node_parallel(&progress_clone);
+            (device_fs, scan_result)
+        });
+
+        while scan_task.is_finished() == false {
+            print!("\r탐색한 entry: {}", progress.scanned_entries());
+            io::stdout().flush().unwrap();
+            tokio::time::sleep(SCAN_PROGRESS_INTERVAL).await;
+        }
+        println!("\r탐색한 entry: {}", progress.scanned_entries());
+
+        let (device_fs, scan_result) = scan_task.await.unwrap();
+        is_scan_finished.store(true, Ordering::Relaxed);
+
+        match scan_result {
+            Ok(()) => Ok(device_fs),
+            Err(FsError::PartialScan(unreadable_paths)) => {
+                println!(
+                    "읽을 수 없는 경로 {}개는 공유하지 않습니다.",
+                    unreadable_paths.len()
+                );
+                for path in unreadable_paths.iter().take(UNREADABLE_PATHS_SHOWN) {
+                    println!("  {}", path.display());
+                }
+                if unreadable_paths.len() > UNREADABLE_PATHS_SHOWN {
+                    println!(
+                        "  ... ({}개 생략)",
+                        unreadable_paths.len() - UNREADABLE_PATHS_SHOWN
+                    );
+                }
+                Ok(device_fs)
+            }
+            Err(e) => Err(e),
+        }
+    }
 
-                        let mut device_manager_lock = device_manager.lock().unwrap();
-                        let _ = std::mem::replace(&mut *device_manager_lock, _d);
+    // device 목록에 표시할 다른 device의 최근 telemetry를 갱신, 실패하면 이전 값을 유지
+    async fn refresh_telemetry(&self, device_manager: &Arc<Mutex<DeviceManager>>) {
+        match request::get_telemetry(&self.master_addr, self.device_manager_uuid).await {
+            Ok(id_telemetry_map) => {
+                device_manager.lock().unwrap().id_telemetry_map = id_telemetry_map;
+            }
+            Err(e) => log::warn!("telemetry를 갱신하지 못했습니다: {}", e),
+        }
+    }
+
+    async fn request_expand(&self, fs_uuid: Uuid, subtree_path: String) {
+        match request::post_device_fs_expand(
+            &self.master_addr,
+            self.device_manager_uuid,
+            fs_uuid,
+            subtree_path,
+        )
+        .await
+        {
+            Ok(status) if status.is_success() => {
+                println!("Device에 탐색을 요청했습니다. 완료되면 FileSystem에 반영됩니다.");
+            }
+            Ok(status) => println!("탐색 요청이 거절되었습니다: {}", status),
+            Err(e) => println!("서버와의 연결상태를 다시 확인해주시기 바랍니다. {}", e),
+        }
+    }
+
+    fn watch_device_fs(&self) {
+        let root_path = match self.device_fs.lock().unwrap().as_ref() {
+            Some(device_fs) => std::path::PathBuf::from(device_fs.node.file_name()),
+            None => return,
+        };
+
+        let fs_watcher = match FsWatcher::new(&root_path) {
+            Ok(fs_watcher) => fs_watcher,
+            Err(e) => {
+                println!(
+                    "file system 변경 감시를 시작하지 못했습니다. 변경 사항이 group에 반영되지 않습니다: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        let master_addr_clone = self.master_addr.clone();
+        let device_manager_uuid_clone = self.device_manager_uuid.clone();
+        let device_uuid_clone = self.device_uuid.clone();
+        let device_fs_clone = Arc::clone(&self.device_fs);
+        let wire_format = self.client_config.wire_format();
+        let runtime_handle = tokio::runtime::Handle::current();
+
+        std::thread::spawn(move || {
+            while let Some(changed_dirs) = fs_watcher.wait_changes(WATCH_DEBOUNCE) {
+                for changed_dir in changed_dirs {
+                    let update = {
+                        let mut device_fs_lock = device_fs_clone.lock().unwrap();
+                        match device_fs_lock.as_mut() {
+                            Some(device_fs) => device_fs
+                                .refresh_subtree(&changed_dir)
+                                .and_then(|update| device_fs.publish_update(update)),
+                            None => None,
+                        }
+                    };
+
+                    if let Some(update) = update {
+                        // 변경된 subtree만 master에 전달
+                        let res = runtime_handle.block_on(request::patch_device_fs_subtree(
+                            &master_addr_clone,
+                            device_manager_uuid_clone,
+                            device_uuid_clone,
+                            update,
+                            wire_format,
+                        ));
+                        if let Err(e) = res {
+                            println!("변경된 file system을 master에 반영하지 못했습니다: {}", e);
+                        }
                     }
-                    _ => {}
                 }
             }
         });
@@ -80,6 +374,30 @@ impl Cli {
         }
     }
 
+    // 목록의 번호, label, UUID(혹은 앞 8자리), "#tag"로 device를 선택
+    fn select_device(indent: usize, prompt: &str, device_manager: &DeviceManager) -> Option<Uuid> {
+        Cli::print_indent(indent, &format!("{} (번호, label, #tag): ", prompt));
+        io::stdout().flush().unwrap();
+
+        let mut selected_device = String::new();
+        io::stdin().read_line(&mut selected_device).unwrap();
+        let selected_device = selected_device.trim();
+
+        let device_spec_map = &device_manager.id_spec_map;
+        let selected_device_key = match selected_device.parse::<usize>() {
+            Ok(num) => device_spec_map.keys().nth(num).copied(),
+            Err(_) => label::find_device(device_spec_map, selected_device),
+        };
+        if selected_device_key.is_none() {
+            println!(
+                "존재하지 않거나 하나로 정해지지 않는 Device입니다: {}",
+                selected_device
+            );
+        }
+
+        selected_device_key
+    }
+
     fn render_device_lst(&self, indent: usize, device_manager: &DeviceManager) {
         let device_spec_map = &device_manager.id_spec_map;
         let device_uuid_lst: Vec<_> = device_spec_map.keys().collect();
@@ -88,82 +406,439 @@ impl Cli {
 
         for (idx, uuid) in device_uuid_lst.iter().enumerate() {
             let _spec = device_spec_map.get(uuid).unwrap();
+            let tags: Vec<String> = _spec.tags.iter().map(|tag| format!("#{}", tag)).collect();
             Cli::println_indent(
                 indent + 1,
-                &format!("{}> {}({})_{}", idx, _spec.os, _spec.os_version, _spec.ip),
+                &format!(
+                    "{}> {} {}",
+                    idx,
+                    namespace::device_label(uuid, Some(_spec)).bold(),
+                    tags.join(" ")
+                ),
             );
+            Cli::println_indent(
+                indent + 2,
+                &format!(
+                    "{} {}({}, {})_{}:{}",
+                    _spec.hostname,
+                    _spec.os,
+                    _spec.os_version,
+                    _spec.arch,
+                    _spec.ip,
+                    _spec.listen_port
+                ),
+            );
+            let candidates: Vec<String> = _spec
+                .connect_candidates()
+                .iter()
+                .map(|endpoint| endpoint.to_string())
+                .collect();
+            Cli::println_indent(indent + 2, &format!("주소 후보: {}", candidates.join(", ")));
+
+            let free_disk = match _spec.free_disk {
+                Some(free_disk) => format_size(free_disk),
+                None => "알 수 없음".to_string(),
+            };
+            Cli::println_indent(
+                indent + 2,
+                &format!(
+                    "core {}개, 메모리 {} / {} 여유, 디스크 {} 여유, client v{}",
+                    _spec.cpu_cores,
+                    format_size(_spec.free_memory),
+                    format_size(_spec.total_memory),
+                    free_disk,
+                    _spec.client_version
+                ),
+            );
+
+            if let Some(telemetry) = device_manager.id_telemetry_map.get(uuid) {
+                let load_average = match telemetry.load_average {
+                    Some(load_average) => format!("{:.2}", load_average),
+                    None => "-".to_string(),
+                };
+                Cli::println_indent(
+                    indent + 2,
+                    &format!(
+                        "load {}, 전송 {}, 실행 {}분",
+                        load_average,
+                        format_size(telemetry.bytes_served),
+                        telemetry.uptime / 60
+                    ),
+                );
+            }
         }
     }
 
-    fn render_file_system(&self, indent: usize, device_manager: &DeviceManager) {
+    // 탐색하지 않은 directory를 펼치려는 경우 (device uuid, 경로)를 반환
+    fn render_file_system(
+        &self,
+        indent: usize,
+        device_manager: &DeviceManager,
+    ) -> Option<(Uuid, String)> {
         self.render_device_lst(indent, device_manager);
         let device_fs_map = &device_manager.id_fs_map;
 
-        Cli::print_indent(indent, "\nFileSystem을 확인할 Device를 선택해주세요: ");
+        let selected_device_key = Cli::select_device(
+            indent,
+            "\nFileSystem을 확인할 Device를 선택해주세요",
+            device_manager,
+        )?;
+        let selected_device_fs = match device_fs_map.get(&selected_device_key) {
+            Some(fs) => fs,
+            None => {
+                println!("Device의 file system이 아직 등록되지 않았습니다.");
+                return None;
+            }
+        };
+        println!();
+        for line in self
+            .client_config
+            .tree
+            .render(&selected_device_fs.node)
+            .lines()
+        {
+            Cli::println_indent(indent, line);
+        }
+
+        self.last_seen_fs
+            .lock()
+            .unwrap()
+            .insert(selected_device_key, selected_device_fs.clone());
+
+        if selected_device_fs.node.has_unexpanded() == false {
+            return None;
+        }
+
+        Cli::print_indent(
+            indent,
+            "\n펼칠 directory(...)의 경로를 입력해주세요 (건너뛰려면 Enter): ",
+        );
         io::stdout().flush().unwrap();
 
-        let mut selected_device_uuid = String::new();
-        io::stdin().read_line(&mut selected_device_uuid).unwrap();
+        let mut subtree_path = String::new();
+        io::stdin().read_line(&mut subtree_path).unwrap();
+        let subtree_path = subtree_path.trim();
+        if subtree_path.is_empty() {
+            return None;
+        }
 
-        let selected_num: usize = match selected_device_uuid.trim().parse() {
-            Ok(num) => num,
-            Err(e) => {
-                println!("숫자가 아닌 값을 입력했습니다: {}", e.to_string());
+        Some((selected_device_key, subtree_path.to_string()))
+    }
+
+    fn render_file_changes(&self, indent: usize, device_manager: &DeviceManager) {
+        self.render_device_lst(indent, device_manager);
+        let device_fs_map = &device_manager.id_fs_map;
+
+        let selected_device_key = match Cli::select_device(
+            indent,
+            "\n변경 사항을 확인할 Device를 선택해주세요",
+            device_manager,
+        ) {
+            Some(selected_device_key) => selected_device_key,
+            None => return,
+        };
+        let selected_device_fs = match device_fs_map.get(&selected_device_key) {
+            Some(fs) => fs,
+            None => {
+                println!("Device의 file system이 아직 등록되지 않았습니다.");
                 return;
             }
         };
 
-        let selected_device_fs = device_fs_map
-            .get(&device_fs_map.keys().nth(selected_num).unwrap())
-            .unwrap();
-        Cli::print_indent(indent, &format!("{:?}", selected_device_fs));
+        let mut last_seen_fs_lock = self.last_seen_fs.lock().unwrap();
+        match last_seen_fs_lock.get(&selected_device_key) {
+            Some(last_seen_fs) => {
+                let changes = last_seen_fs.diff(selected_device_fs);
+                if changes.is_empty() {
+                    Cli::println_indent(indent, "\n마지막으로 확인한 이후 변경 사항이 없습니다.");
+                } else {
+                    Cli::println_indent(indent, "\n마지막으로 확인한 이후 변경 사항: ");
+                    for change in changes {
+                        Cli::println_indent(indent + 1, &change.to_string());
+                    }
+                }
+            }
+            None => {
+                Cli::println_indent(
+                    indent,
+                    "\n처음 확인하는 Device입니다. 현재 상태를 기준으로 이후 변경 사항을 보여줍니다.",
+                );
+            }
+        }
+
+        last_seen_fs_lock.insert(selected_device_key, selected_device_fs.clone());
     }
 
-    fn render_file_transfer(&self, indent: usize, device_manager: &DeviceManager) {
+    fn render_file_search(&self, indent: usize, device_manager: &DeviceManager) {
         self.render_device_lst(indent, device_manager);
-        let device_spec_map = &device_manager.id_spec_map;
+        let device_fs_map = &device_manager.id_fs_map;
+
+        let selected_device_key =
+            match Cli::select_device(indent, "\n검색할 Device를 선택해주세요", device_manager)
+            {
+                Some(selected_device_key) => selected_device_key,
+                None => return,
+            };
+        let selected_device_fs = match device_fs_map.get(&selected_device_key) {
+            Some(fs) => fs,
+            None => {
+                println!("Device의 file system이 아직 등록되지 않았습니다.");
+                return;
+            }
+        };
+
+        Cli::println_indent(indent, "\n검색어를 입력해주세요.");
+        Cli::println_indent(indent + 1, "ls <경로>: directory 목록 (예: ls docs)");
+        Cli::println_indent(
+            indent + 1,
+            "re:<정규식>: 이름 검색 (예: re:^report.*\\.pdf$)",
+        );
+        Cli::println_indent(indent + 1, "<glob>: 경로 검색 (예: **/*.md)");
+        Cli::print_indent(indent, "> ");
+        io::stdout().flush().unwrap();
+
+        let mut query = String::new();
+        io::stdin().read_line(&mut query).unwrap();
+        let query = query.trim();
+
+        let list_dir_path = match query {
+            "ls" => Some(""),
+            _ => query.strip_prefix("ls "),
+        };
+
+        let result = if let Some(dir_path) = list_dir_path {
+            match selected_device_fs.list_dir(dir_path.trim()) {
+                Some(paths) => Ok(paths),
+                None => {
+                    Cli::println_indent(indent, "존재하지 않는 directory입니다.");
+                    return;
+                }
+            }
+        } else if let Some(pattern) = query.strip_prefix("re:") {
+            selected_device_fs.search(pattern)
+        } else {
+            selected_device_fs.glob(query)
+        };
+
+        match result {
+            Ok(paths) if paths.is_empty() => {
+                Cli::println_indent(indent, "\n일치하는 파일이 없습니다.");
+            }
+            Ok(paths) => {
+                Cli::println_indent(indent, &format!("\n검색 결과 ({}개): ", paths.len()));
+                for path in paths {
+                    Cli::println_indent(indent + 1, &format!("{} [{}]", path, node_id(&path)));
+                }
+            }
+            Err(e) => Cli::println_indent(indent, &format!("잘못된 검색어입니다: {}", e)),
+        }
+    }
 
-        Cli::print_indent(indent, "\nFileTransfer을 요청할 Device를 선택해주세요: ");
+    fn render_namespace(&self, indent: usize, device_manager: &DeviceManager) {
+        Cli::print_indent(
+            indent,
+            "\n보기 방식을 선택해주세요 (0: device별, 1: 같은 경로 합치기): ",
+        );
+        io::stdout().flush().unwrap();
+
+        let mut selected_view = String::new();
+        io::stdin().read_line(&mut selected_view).unwrap();
+
+        let view = match selected_view.trim() {
+            "0" => NamespaceView::ByDevice,
+            "1" => NamespaceView::Union,
+            _ => {
+                println!("존재하지 않는 보기 방식입니다.");
+                return;
+            }
+        };
+
+        let namespace = namespace::build_namespace(
+            &device_manager.id_spec_map,
+            &device_manager.id_fs_map,
+            view,
+        );
+        println!();
+        for line in self.client_config.tree.render(&namespace).lines() {
+            Cli::println_indent(indent, line);
+        }
+        if view == NamespaceView::Union {
+            Cli::println_indent(
+                indent,
+                "\n(내용이 다른 같은 경로의 파일은 \"이름~device\"로 표시됩니다.)",
+            );
+        }
+    }
+
+    // Enter는 현재 값 유지, "-"는 삭제
+    fn read_label_field(indent: usize, prompt: &str, current: &str) -> String {
+        Cli::print_indent(
+            indent,
+            &format!("{} [{}] (유지: Enter, 삭제: -): ", prompt, current),
+        );
         io::stdout().flush().unwrap();
 
-        let mut selected_device_uuid = String::new();
-        io::stdin().read_line(&mut selected_device_uuid).unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        match input.trim() {
+            "" => current.to_string(),
+            "-" => String::new(),
+            input => input.to_string(),
+        }
+    }
+
+    // 변경할 label과 tag를 입력받음, 변경 사항이 없으면 None
+    fn render_device_label(
+        &self,
+        indent: usize,
+        device_manager: &DeviceManager,
+    ) -> Option<DeviceLabel> {
+        let current = device_manager
+            .id_spec_map
+            .get(&self.device_uuid)
+            .map(|spec| spec.device_label())
+            .unwrap_or_default();
+
+        let label = Cli::read_label_field(indent, "\n이 device의 label", &current.label);
+        let tags = Cli::read_label_field(indent, "tag 목록(','로 구분)", &current.tags.join(","));
+        let device_label = DeviceLabel {
+            label,
+            tags: tags
+                .split(',')
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| tag.is_empty() == false)
+                .collect(),
+        };
+
+        if device_label == current {
+            Cli::println_indent(indent, "변경 사항이 없습니다.");
+            return None;
+        }
+        Some(device_label)
+    }
+
+    // master에 반영된 label을 다음 실행에도 사용하도록 저장
+    async fn update_device_label(&self, device_label: DeviceLabel) {
+        match request::put_device_label(
+            &self.master_addr,
+            self.device_manager_uuid,
+            self.device_uuid,
+            &device_label,
+        )
+        .await
+        {
+            Ok(device_label) => {
+                println!(
+                    "label을 변경했습니다: {} {:?}",
+                    device_label.label.bold(),
+                    device_label.tags
+                );
+                if let Err(e) = config::save_device_label(&device_label) {
+                    println!(
+                        "변경한 label을 저장하지 못했습니다. 다음 실행에는 반영되지 않습니다: {}",
+                        e
+                    );
+                }
+            }
+            Err(e) => println!("label을 변경하지 못했습니다. {}", e),
+        }
+    }
 
-        let selected_num: usize = selected_device_uuid.trim().parse().unwrap();
-        let selected_device_spec = device_spec_map
-            .get(&device_spec_map.keys().nth(selected_num).unwrap())
-            .unwrap();
-        let peer_ip = selected_device_spec.ip.clone();
-        let peer_port = selected_device_spec.listen_port.clone();
-        let peer_addr = format!("{}:{}", peer_ip, peer_port);
+    fn render_file_transfer(&self, indent: usize, device_manager: &DeviceManager) {
+        self.render_device_lst(indent, device_manager);
+        let device_spec_map = &device_manager.id_spec_map;
+        let device_fs_map = &device_manager.id_fs_map;
+
+        let selected_device_key = match Cli::select_device(
+            indent,
+            "\nFileTransfer을 요청할 Device를 선택해주세요",
+            device_manager,
+        ) {
+            Some(selected_device_key) => selected_device_key,
+            None => return,
+        };
+        let selected_device_spec = device_spec_map.get(&selected_device_key).unwrap();
 
-        let mut stream = match self.network.connect(peer_addr) {
-            Ok(stream) => stream,
+        if Capabilities::current()
+            .choose_transfer_protocol(&selected_device_spec.capabilities)
+            .is_none()
+        {
+            Cli::println_indent(
+                indent,
+                "Device가 지원하는 전송 방식이 없습니다. Device의 client version을 확인해주세요.",
+            );
+            return;
+        }
+
+        // device가 알린 주소 후보를 우선순위 순서로 시도
+        let candidates = selected_device_spec.connect_candidates();
+        let mut stream = match self.network.connect_any(&candidates) {
+            Ok((stream, peer_addr)) => {
+                log::debug!("Device에 연결했습니다: {}", peer_addr);
+                stream
+            }
             Err(e) => {
-                println!("Device와의 연결에 실패했습니다: {:?}", e.to_string());
+                println!(
+                    "Device와의 연결에 실패했습니다({}개 주소 시도): {:?}",
+                    candidates.len(),
+                    e.to_string()
+                );
                 return;
             }
         };
 
-        Cli::print_indent(indent, "\n전송받을 Filename(절대경로)을 작성해주세요: ");
+        Cli::print_indent(
+            indent,
+            "\n전송받을 파일의 경로(root 기준 상대경로) 혹은 ID를 작성해주세요: ",
+        );
         io::stdout().flush().unwrap();
 
-        let mut request_file_name = String::new();
-        io::stdin().read_line(&mut request_file_name).unwrap();
+        let mut request_path = String::new();
+        io::stdin().read_line(&mut request_path).unwrap();
+
+        // master에 등록된 tree에서 찾은 entry만 요청하고, 전송받은 파일을 tree의 hash와 비교
+        let selected_entry = device_fs_map
+            .get(&selected_device_key)
+            .and_then(|fs| fs.resolve_entry(request_path.trim_end()));
+        let (relative_path, node) = match selected_entry {
+            Some((_, node)) if node.share_policy().is_downloadable() == false => {
+                Cli::println_indent(indent, "목록만 공개된 파일이므로 전송받을 수 없습니다.");
+                return;
+            }
+            Some((relative_path, node)) if node.kind() == FileKind::File => (relative_path, node),
+            Some(_) => {
+                Cli::println_indent(indent, "파일만 전송받을 수 있습니다.");
+                return;
+            }
+            None => {
+                Cli::println_indent(indent, "Device의 file system에 존재하지 않는 경로입니다.");
+                return;
+            }
+        };
 
         self.network
-            .send_request(&mut stream, request_file_name.trim_end().to_string());
+            .send_request(&mut stream, relative_path, node.hash());
     } // network 모듈? interface 활용
 }
 
 // TODO: gui와 공통된 부분 빼기
 impl interface::Interface for Cli {
-    fn new(master_addr: String, listen_port: u16, file_storage: String) -> Self {
+    fn new(master_addr: String, client_config: ClientConfig) -> Self {
+        // listener와 watcher가 같은 file system을 공유
+        let device_fs = Arc::new(Mutex::new(None));
+
         Cli {
             master_addr,
             device_manager_uuid: Uuid::nil(),
             device_uuid: Uuid::new_v4(),
-            network: TcpNetwork::new(listen_port, file_storage),
+            network: TcpNetwork::new(
+                client_config.listen_port,
+                client_config.file_storage.clone(),
+                Arc::clone(&device_fs),
+            ),
+            client_config,
+            device_fs,
+            last_seen_fs: Arc::new(Mutex::new(BTreeMap::new())),
         }
     }
 
@@ -174,6 +849,7 @@ impl interface::Interface for Cli {
             network_clone.listen(listener);
         });
 
+        self.handshake().await;
         self.enter_group().await;
         // TODO: self.device_manager_uuid가 업데이트되는데, 이 uuid와 device의 uuid(spec | fs)를 websocket에 전달
         // TODO: websocket에서 진행하는 등록은 socket등록이지, clientGroup객체에의 등록이 아님 -> 아래 과정도 진행해야됨
@@ -183,8 +859,10 @@ impl interface::Interface for Cli {
             "DeviceManager".bold(),
             self.device_manager_uuid.to_string().yellow().bold()
         );
-        let spec_uuid = self.register_device_spec(self.device_manager_uuid).await;
+        // spec에 공유 중인 root의 여유 공간을 포함하기 위해 fs를 먼저 등록
         let _ = self.register_device_fs(self.device_manager_uuid).await;
+        let spec_uuid = self.register_device_spec(self.device_manager_uuid).await;
+        self.watch_device_fs();
 
         let websocket_url = Url::parse(&format!(
             "ws://{}/ws/{}/{}",
@@ -200,12 +878,14 @@ impl interface::Interface for Cli {
         // println!("WebSocket 연결 성공: {:?}", _res);
 
         let device_manager =
-            request::get_device_manager(&self.master_addr, self.device_manager_uuid)
-                .await
-                .unwrap();
+            match request::get_device_manager(&self.master_addr, self.device_manager_uuid).await {
+                Ok(device_manager) => device_manager,
+                Err(e) => self.exit(Some(e.to_string())).await,
+            };
         let device_manager = Arc::new(Mutex::new(device_manager));
 
-        let (mut write, mut read) = ws_stream.split();
+        let (write, read) = ws_stream.split();
+        self.report_telemetry(write);
 
         let device_manager_clone = Arc::clone(&device_manager);
         self.sync_device_manager(device_manager_clone, read).await;
@@ -249,22 +929,53 @@ impl interface::Interface for Cli {
                 "------------------------------------------------------",
             );
 
+            let action = action::ActionNum::try_from(action_num).unwrap();
+            if matches!(
+                action,
+                action::ActionNum::Exit | action::ActionNum::Undefined
+            ) == false
+            {
+                self.refresh_telemetry(&device_manager).await;
+            }
+
             let device_manager_lock = device_manager.lock().unwrap();
-            match action::ActionNum::try_from(action_num).unwrap() {
+            let mut expand_request: Option<(Uuid, String)> = None;
+            let mut label_update: Option<DeviceLabel> = None;
+            match action {
                 action::ActionNum::DeviceList => {
                     self.render_device_lst(indent + 1, &device_manager_lock)
                 }
                 action::ActionNum::FileSystem => {
-                    self.render_file_system(indent + 1, &device_manager_lock)
+                    expand_request = self.render_file_system(indent + 1, &device_manager_lock)
+                }
+                action::ActionNum::FileChanges => {
+                    self.render_file_changes(indent + 1, &device_manager_lock)
                 }
                 action::ActionNum::FileTransfer => {
                     self.render_file_transfer(indent + 1, &device_manager_lock)
                 }
+                action::ActionNum::FileSearch => {
+                    self.render_file_search(indent + 1, &device_manager_lock)
+                }
+                action::ActionNum::Namespace => {
+                    self.render_namespace(indent + 1, &device_manager_lock)
+                }
+                action::ActionNum::DeviceLabel => {
+                    label_update = self.render_device_label(indent + 1, &device_manager_lock)
+                }
                 action::ActionNum::Exit => self.exit(None).await,
                 action::ActionNum::Undefined => {
                     Cli::println_indent(indent, "정의되지 않은 동작입니다.");
                 }
             };
+            drop(device_manager_lock);
+
+            if let Some((fs_uuid, subtree_path)) = expand_request {
+                self.request_expand(fs_uuid, subtree_path).await;
+            }
+            if let Some(device_label) = label_update {
+                self.update_device_label(device_label).await;
+            }
 
             Cli::println_indent(
                 indent,
@@ -310,34 +1021,78 @@ impl interface::Interface for Cli {
         let mut system = System::new_all();
         system.refresh_all();
 
-        let os = system
-            .name()
-            .expect("system의 name을 구하는데 문제가 발생했습니다.");
-        let os_version = system
-            .os_version()
-            .expect("system의 os version 구하는데 문제가 발생했습니다.");
-        let spec = DeviceSpec {
+        // 공유 중인 root가 있는 volume의 여유 공간
+        let volumes: Vec<_> = system
+            .disks()
+            .iter()
+            .map(|disk| (disk.mount_point().to_path_buf(), disk.available_space()))
+            .collect();
+        let free_disk = match self.device_fs.lock().unwrap().as_ref() {
+            Some(device_fs) => {
+                spec::volume_free_space(&volumes, std::path::Path::new(device_fs.node.file_name()))
+            }
+            None => None,
+        };
+
+        // 저장된 label을 기본값으로 제안
+        let saved_label = self.client_config.device_label();
+        let label = Cli::read_label_field(0, "Group에서 사용할 device label", &saved_label.label);
+
+        let mut spec = DeviceSpec {
             ip: "".to_string(),
-            os,
-            os_version,
-            listen_port: self.network.listen_port.to_string(),
+            os: system.name().unwrap_or_default(),
+            os_version: system.os_version().unwrap_or_default(),
+            listen_port: self.network.listen_port,
+            hostname: system.host_name().unwrap_or_default(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpu_cores: system.cpus().len(),
+            total_memory: system.total_memory(),
+            free_memory: system.available_memory(),
+            free_disk,
+            client_version: env!("CARGO_PKG_VERSION").to_string(),
+            capabilities: Capabilities::current(),
+            label,
+            tags: saved_label.tags.clone(),
+            endpoints: self
+                .network
+                .local_endpoints(&self.client_config.advertise_addrs),
         };
 
-        match request::post_device_spec(&self.master_addr, manager_uuid, self.device_uuid, spec)
+        // label이 group의 다른 device와 겹치거나 사용할 수 없으면 다시 입력받음
+        loop {
+            match request::post_device_spec(
+                &self.master_addr,
+                manager_uuid,
+                self.device_uuid,
+                spec.clone(),
+            )
             .await
-        {
-            Ok(uuid) => {
-                println!(
-                    "{} 등록 완료: {}",
-                    "device spec".bold(),
-                    uuid.to_string().yellow().bold()
-                );
+            {
+                Ok(uuid) => {
+                    println!(
+                        "{} 등록 완료: {}",
+                        "device spec".bold(),
+                        uuid.to_string().yellow().bold()
+                    );
 
-                uuid
-            }
-            Err(e) => {
-                println!("서버와의 연결상태를 다시 확인해주시기 바랍니다. {}", e);
-                process::exit(-1);
+                    if spec.device_label() != saved_label {
+                        if let Err(e) = config::save_device_label(&spec.device_label()) {
+                            println!("label을 저장하지 못했습니다: {}", e);
+                        }
+                    }
+                    return uuid;
+                }
+                Err(request::RequestError::Rejected(status, reason))
+                    if status == reqwest::StatusCode::CONFLICT
+                        || status == reqwest::StatusCode::BAD_REQUEST =>
+                {
+                    println!("{}", reason);
+                    spec.label = Cli::read_label_field(0, "다른 label", &spec.label);
+                }
+                Err(e) => {
+                    println!("서버와의 연결상태를 다시 확인해주시기 바랍니다. {}", e);
+                    process::exit(-1);
+                }
             }
         }
     }
@@ -347,23 +1102,58 @@ impl interface::Interface for Cli {
         println!("e.g. MacOSX : /Users/username/Desktop/public_dir");
         println!("     Linux  : /home/username/public_dir");
 
-        print!("입력: ");
-        io::stdout().flush().unwrap();
+        // 공유할 수 없는 경로면 다른 경로로 대체하지 않고 다시 입력받음
+        let mut device_fs = loop {
+            print!("입력: ");
+            io::stdout().flush().unwrap();
 
-        let mut device_fs_root: String = String::new();
-        io::stdin()
-            .read_line(&mut device_fs_root)
-            .expect("입력에 실패했습니다.");
+            let mut device_fs_root: String = String::new();
+            io::stdin()
+                .read_line(&mut device_fs_root)
+                .expect("입력에 실패했습니다.");
+
+            match FileSystem::new(device_fs_root.trim()) {
+                Ok(device_fs) => break device_fs,
+                Err(FsError::UnsupportedPlatform(os)) => {
+                    self.exit(Some(format!("지원하지 않는 운영체제입니다: {}", os)))
+                        .await
+                }
+                Err(e) => println!("{} 다른 경로를 입력해주세요.", e),
+            }
+        };
+        device_fs.set_ignore_patterns(&self.client_config.ignore);
+        device_fs.set_symlink_policy(self.client_config.symlink_policy);
+        device_fs.set_scan_depth(self.client_config.scan_depth);
+        device_fs.set_share_policies(self.client_config.share.clone());
+        println!("FileSystem 구성 작업을 시작합니다. (Ctrl+C: 취소)");
+        let device_fs = match Cli::scan_device_fs(device_fs).await {
+            Ok(device_fs) => device_fs,
+            Err(FsError::Cancelled) => {
+                self.exit(Some("FileSystem 구성이 취소되었습니다.".to_string()))
+                    .await
+            }
+            Err(e) => self.exit(Some(e.to_string())).await,
+        };
 
-        let mut device_fs_root_trimmed = device_fs_root.trim().to_owned();
-        let mut device_fs = FileSystem::new(device_fs_root_trimmed.borrow_mut());
-        println!("FileSystem 구성 작업을 시작합니다.");
-        device_fs.init_file_node();
+        let case_collisions = device_fs.case_collisions();
+        if case_collisions.is_empty() == false {
+            println!("대소문자만 다른 entry가 있습니다. 대소문자를 구분하지 않는 device(Windows, macOS)는 각 묶음 중 하나만 전송받을 수 있습니다.");
+            for collision in case_collisions {
+                println!("  {}", collision.join(", "));
+            }
+        }
 
-        match request::post_device_fs(&self.master_addr, manager_uuid, self.device_uuid, device_fs)
-            .await
+        match request::post_device_fs(
+            &self.master_addr,
+            manager_uuid,
+            self.device_uuid,
+            device_fs.published(),
+            self.client_config.wire_format(),
+        )
+        .await
         {
             Ok(uuid) => {
+                *self.device_fs.lock().unwrap() = Some(device_fs);
                 println!(
                     "{} 등록 완료: {}",
                     "device fs".bold(),
@@ -372,6 +1162,10 @@ impl interface::Interface for Cli {
 
                 uuid
             }
+            Err(e @ request::RequestError::Rejected(..)) => {
+                println!("device fs를 등록하지 못했습니다. {}", e);
+                process::exit(-1);
+            }
             Err(e) => {
                 println!("서버와의 연결상태를 다시 확인해주시기 바랍니다. {}", e);
                 process::exit(-1);
@@ -7,7 +7,7 @@ use std::{
 use uuid::Uuid;
 
 use super::super::interface;
-use crate::{network::tcp::network::TcpNetwork, ui::request::DeviceManager};
+use crate::{config::ClientConfig, network::tcp::network::TcpNetwork, ui::request::DeviceManager};
 
 struct App;
 
@@ -20,12 +20,16 @@ pub struct Gui {
 }
 
 impl interface::Interface for Gui {
-    fn new(master_addr: String, listen_port: u16, file_storage: String) -> Self {
+    fn new(master_addr: String, client_config: ClientConfig) -> Self {
         Gui {
             master_addr,
             device_manager_uuid: Uuid::nil(),
             device_uuid: Uuid::nil(),
-            network: TcpNetwork::new(listen_port, file_storage),
+            network: TcpNetwork::new(
+                client_config.listen_port,
+                client_config.file_storage,
+                Arc::new(Mutex::new(None)),
+            ),
         }
     }
     async fn entry(&mut self) {
@@ -2,9 +2,10 @@ use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
 use super::request::DeviceManager;
+use crate::config::ClientConfig;
 
 pub trait Interface {
-    fn new(master_addr: String, listen_port: u16, file_storage: String) -> Self;
+    fn new(master_addr: String, client_config: ClientConfig) -> Self;
     async fn entry(&mut self);
     async fn exit(&self, error_opt: Option<String>) -> !;
     async fn render(&self, device_manager: Arc<Mutex<DeviceManager>>);
@@ -1,8 +1,16 @@
 use std::collections::BTreeMap;
 
-use device::device::{file_sys::FileSystem, spec::DeviceSpec};
+use device::device::{
+    codec::WireFormat,
+    file_sys::{FileSystem, SubtreeUpdate},
+    label::DeviceLabel,
+    protocol::{Capabilities, HandshakeReply},
+    spec::DeviceSpec,
+    telemetry::Telemetry,
+};
 
 use reqwest;
+use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -10,22 +18,127 @@ use uuid::Uuid;
 pub struct DeviceManager {
     pub id_spec_map: BTreeMap<Uuid, DeviceSpec>,
     pub id_fs_map: BTreeMap<Uuid, FileSystem>,
+    #[serde(default)]
+    pub id_telemetry_map: BTreeMap<Uuid, Telemetry>, // device별 가장 최근 telemetry
+}
+
+#[derive(Debug)]
+pub enum RequestError {
+    Http(reqwest::Error),
+    Rejected(reqwest::StatusCode, String), // master가 거절한 요청과 사유
+    Decode(String),                        // master의 응답 형식이 이 client와 다름
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RequestError::Http(e) => write!(f, "{}", e),
+            RequestError::Rejected(status, reason) => {
+                write!(f, "master가 요청을 거절했습니다({}): {}", status, reason)
+            }
+            RequestError::Decode(e) => write!(
+                f,
+                "master의 응답을 해석하지 못했습니다. master와 client의 version을 확인해주세요: {}",
+                e
+            ),
+        }
+    }
+}
+
+impl From<reqwest::Error> for RequestError {
+    fn from(e: reqwest::Error) -> Self {
+        RequestError::Http(e)
+    }
+}
+
+// 거절된 요청이면 응답 body를 사유로 사용
+async fn success_text(response: reqwest::Response) -> Result<String, RequestError> {
+    let status = response.status();
+    let text = response.text().await?;
+
+    match status.is_success() {
+        true => Ok(text),
+        false => Err(RequestError::Rejected(status, text)),
+    }
+}
+
+// group에 참가하기 전에 이 client의 capabilities를 전달하고 master의 capabilities를 받음
+// master가 형식을 알기 전이므로 json으로 전송
+pub async fn post_handshake(
+    master_addr: &str,
+    capabilities: &Capabilities,
+) -> Result<Capabilities, RequestError> {
+    let request_addr = format!("{}/api/handshake", master_addr);
+    let client = reqwest::Client::new();
+
+    let response = client
+        .post(request_addr)
+        .header(
+            CONTENT_TYPE,
+            WireFormat::plain_json().encoding.content_type(),
+        )
+        .body(serde_json::to_string(capabilities).unwrap())
+        .send()
+        .await?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(RequestError::Rejected(
+            status,
+            "master가 handshake를 지원하지 않는 이전 version입니다.".to_string(),
+        ));
+    }
+
+    let text = response.text().await?;
+    let reply: HandshakeReply = match serde_json::from_str(&text) {
+        Ok(reply) => reply,
+        Err(_) if status.is_success() == false => return Err(RequestError::Rejected(status, text)),
+        Err(e) => return Err(RequestError::Decode(e.to_string())),
+    };
+
+    match reply.accepted {
+        true => Ok(reply.capabilities),
+        false => Err(RequestError::Rejected(
+            status,
+            reply.reason.unwrap_or_default(),
+        )),
+    }
 }
 
 pub async fn get_device_manager(
     master_addr: &str,
     manager_uuid: Uuid,
-) -> Result<DeviceManager, reqwest::Error> {
+) -> Result<DeviceManager, RequestError> {
     let request_addr = format!("{}/api/device-manager/{}", master_addr, manager_uuid);
-    let request = match reqwest::get(request_addr).await {
-        Ok(response) => response,
-        Err(e) => return Err(e),
-    };
+    let response = reqwest::get(request_addr).await?;
 
-    let device_manager_str = request.text().await.unwrap();
-    let device_manager = serde_json::from_str(&device_manager_str).unwrap();
+    let device_manager_str = success_text(response).await?;
+    serde_json::from_str(&device_manager_str).map_err(|e| RequestError::Decode(e.to_string()))
+}
 
-    Ok(device_manager)
+// master의 GET /telemetry 응답 중 목록에 표시할 가장 최근 값만 사용
+#[derive(Deserialize)]
+struct DeviceTelemetry {
+    latest: Option<Telemetry>,
+}
+
+// telemetry는 group에 전달되지 않으므로 device 목록을 보여줄 때마다 받아옴
+pub async fn get_telemetry(
+    master_addr: &str,
+    manager_uuid: Uuid,
+) -> Result<BTreeMap<Uuid, Telemetry>, RequestError> {
+    let request_addr = format!(
+        "{}/api/device-manager/{}/telemetry",
+        master_addr, manager_uuid
+    );
+    let response = reqwest::get(request_addr).await?;
+
+    let telemetry_str = success_text(response).await?;
+    let device_telemetry: BTreeMap<Uuid, DeviceTelemetry> =
+        serde_json::from_str(&telemetry_str).map_err(|e| RequestError::Decode(e.to_string()))?;
+    Ok(device_telemetry
+        .into_iter()
+        .filter_map(|(id, device_telemetry)| device_telemetry.latest.map(|latest| (id, latest)))
+        .collect())
 }
 
 pub async fn post_device_manager(master_addr: &str) -> Result<Uuid, reqwest::Error> {
@@ -47,7 +160,7 @@ pub async fn post_device_spec(
     manager_uuid: Uuid,
     new_spec_uuid: Uuid,
     spec: DeviceSpec,
-) -> Result<Uuid, reqwest::Error> {
+) -> Result<Uuid, RequestError> {
     let request_addr = format!(
         "{}/api/device-manager/{}/spec/{}",
         master_addr, manager_uuid, new_spec_uuid
@@ -55,15 +168,15 @@ pub async fn post_device_spec(
     let client = reqwest::Client::new();
 
     let serialized_spec = serde_json::to_string(&spec).unwrap();
-    let request = match client.post(request_addr).body(serialized_spec).send().await {
-        Ok(response) => response,
-        Err(e) => return Err(e),
-    };
-
-    let spec_uuid_str = request.text().await.unwrap();
-    let spec_uuid = Uuid::parse_str(&spec_uuid_str).unwrap();
+    let response = client
+        .post(request_addr)
+        .body(serialized_spec)
+        .send()
+        .await?;
 
-    Ok(spec_uuid)
+    // 호환되지 않는 spec이면 master가 사유와 함께 거절
+    let spec_uuid_str = success_text(response).await?;
+    Uuid::parse_str(&spec_uuid_str).map_err(|e| RequestError::Decode(e.to_string()))
 }
 
 pub async fn post_device_fs(
@@ -71,23 +184,101 @@ pub async fn post_device_fs(
     manager_uuid: Uuid,
     new_fs_uuid: Uuid,
     fs: FileSystem,
-) -> Result<Uuid, reqwest::Error> {
+    wire_format: WireFormat,
+) -> Result<Uuid, RequestError> {
     let request_addr = format!(
         "{}/api/device-manager/{}/fs/{}",
         master_addr, manager_uuid, new_fs_uuid
     );
     let client = reqwest::Client::new();
 
-    let serialized_fs = serde_json::to_string(&fs).unwrap();
-    let request = match client.post(request_addr).body(serialized_fs).send().await {
+    // tree가 크므로 설정된 형식으로 압축해서 전송
+    let serialized_fs = wire_format.encode(&fs).unwrap();
+    let response = client
+        .post(request_addr)
+        .header(CONTENT_TYPE, wire_format.encoding.content_type())
+        .header(CONTENT_ENCODING, wire_format.compression.content_encoding())
+        .body(serialized_fs)
+        .send()
+        .await?;
+
+    // tree가 master의 제한보다 크거나 해석할 수 없으면 거절됨
+    let fs_uuid_str = success_text(response).await?;
+    Uuid::parse_str(&fs_uuid_str).map_err(|e| RequestError::Decode(e.to_string()))
+}
+
+pub async fn post_device_fs_expand(
+    master_addr: &str,
+    manager_uuid: Uuid,
+    fs_uuid: Uuid,
+    subtree_path: String,
+) -> Result<reqwest::StatusCode, reqwest::Error> {
+    let request_addr = format!(
+        "{}/api/device-manager/{}/fs/{}/expand",
+        master_addr, manager_uuid, fs_uuid
+    );
+    let client = reqwest::Client::new();
+
+    let request = match client.post(request_addr).body(subtree_path).send().await {
         Ok(response) => response,
         Err(e) => return Err(e),
     };
 
-    let fs_uuid_str = request.text().await.unwrap();
-    let fs_uuid = Uuid::parse_str(&fs_uuid_str).unwrap();
+    Ok(request.status())
+}
 
-    Ok(fs_uuid)
+// master가 정규화해서 반영한 label을 반환, group에서 사용 중인 label이면 거절됨
+pub async fn put_device_label(
+    master_addr: &str,
+    manager_uuid: Uuid,
+    spec_uuid: Uuid,
+    device_label: &DeviceLabel,
+) -> Result<DeviceLabel, RequestError> {
+    let request_addr = format!(
+        "{}/api/device-manager/{}/spec/{}/label",
+        master_addr, manager_uuid, spec_uuid
+    );
+    let client = reqwest::Client::new();
+
+    let response = client
+        .put(request_addr)
+        .header(
+            CONTENT_TYPE,
+            WireFormat::plain_json().encoding.content_type(),
+        )
+        .body(serde_json::to_string(device_label).unwrap())
+        .send()
+        .await?;
+
+    let device_label_str = success_text(response).await?;
+    serde_json::from_str(&device_label_str).map_err(|e| RequestError::Decode(e.to_string()))
+}
+
+pub async fn patch_device_fs_subtree(
+    master_addr: &str,
+    manager_uuid: Uuid,
+    fs_uuid: Uuid,
+    update: SubtreeUpdate,
+    wire_format: WireFormat,
+) -> Result<Uuid, RequestError> {
+    let request_addr = format!(
+        "{}/api/device-manager/{}/fs/{}/subtree",
+        master_addr, manager_uuid, fs_uuid
+    );
+    let client = reqwest::Client::new();
+
+    let serialized_update = wire_format.encode(&update).unwrap();
+    let response = client
+        .patch(request_addr)
+        .header(CONTENT_TYPE, wire_format.encoding.content_type())
+        .header(CONTENT_ENCODING, wire_format.compression.content_encoding())
+        .body(serialized_update)
+        .send()
+        .await?;
+
+    // 등록되지 않은 fs거나 반영할 수 없는 update면 거절됨
+    let fs_uuid_str = success_text(response).await?;
+    Uuid::parse_str(&fs_uuid_str).map_err(|e| RequestError::Decode(e.to_string()))
 }
 
 pub async fn delete_device_manager(
@@ -0,0 +1,236 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+const ZSTD_LEVEL: i32 = 3;
+
+// master와 client 사이에 FileSystem 등을 주고받을 때의 직렬화 형식 (Content-Type)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Json, // 사람이 읽을 수 있으므로 debugging 용도
+    #[default]
+    Cbor,
+}
+
+// 직렬화된 body의 압축 방식 (Content-Encoding)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    Identity,
+    Gzip,
+    #[default]
+    Zstd,
+}
+
+#[derive(Debug)]
+pub enum CodecError {
+    UnsupportedContentType(String),
+    UnsupportedContentEncoding(String),
+    Serialize(String),
+    Deserialize(String),
+    Compression(std::io::Error),
+}
+
+impl std::fmt::Display for CodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CodecError::UnsupportedContentType(t) => {
+                write!(f, "지원하지 않는 Content-Type입니다: {}", t)
+            }
+            CodecError::UnsupportedContentEncoding(e) => {
+                write!(f, "지원하지 않는 Content-Encoding입니다: {}", e)
+            }
+            CodecError::Serialize(e) => write!(f, "직렬화에 실패했습니다: {}", e),
+            CodecError::Deserialize(e) => write!(f, "역직렬화에 실패했습니다: {}", e),
+            CodecError::Compression(e) => write!(f, "압축 처리에 실패했습니다: {}", e),
+        }
+    }
+}
+
+impl Encoding {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            Encoding::Cbor => "application/cbor",
+        }
+    }
+
+    // header가 없으면 이전 client와의 호환을 위해 json으로 간주
+    pub fn from_content_type(content_type: Option<&str>) -> Result<Self, CodecError> {
+        let mime = match content_type {
+            Some(content_type) => content_type.split(';').next().unwrap_or("").trim(),
+            None => return Ok(Encoding::Json),
+        };
+
+        match mime.to_ascii_lowercase().as_str() {
+            "" | "application/json" | "text/plain" => Ok(Encoding::Json),
+            "application/cbor" => Ok(Encoding::Cbor),
+            _ => Err(CodecError::UnsupportedContentType(mime.to_string())),
+        }
+    }
+}
+
+impl Compression {
+    pub fn content_encoding(&self) -> &'static str {
+        match self {
+            Compression::Identity => "identity",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    pub fn from_content_encoding(content_encoding: Option<&str>) -> Result<Self, CodecError> {
+        let content_encoding = content_encoding.unwrap_or("").trim().to_ascii_lowercase();
+
+        match content_encoding.as_str() {
+            "" | "identity" => Ok(Compression::Identity),
+            "gzip" => Ok(Compression::Gzip),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(CodecError::UnsupportedContentEncoding(content_encoding)),
+        }
+    }
+
+    fn compress(&self, bytes: Vec<u8>) -> Result<Vec<u8>, CodecError> {
+        match self {
+            Compression::Identity => Ok(bytes),
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(&bytes).map_err(CodecError::Compression)?;
+                encoder.finish().map_err(CodecError::Compression)
+            }
+            Compression::Zstd => {
+                zstd::encode_all(bytes.as_slice(), ZSTD_LEVEL).map_err(CodecError::Compression)
+            }
+        }
+    }
+
+    fn decompress(&self, bytes: &[u8]) -> Result<Vec<u8>, CodecError> {
+        match self {
+            Compression::Identity => Ok(bytes.to_vec()),
+            Compression::Gzip => {
+                let mut decompressed = Vec::new();
+                GzDecoder::new(bytes)
+                    .read_to_end(&mut decompressed)
+                    .map_err(CodecError::Compression)?;
+                Ok(decompressed)
+            }
+            Compression::Zstd => zstd::decode_all(bytes).map_err(CodecError::Compression),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WireFormat {
+    pub encoding: Encoding,
+    pub compression: Compression,
+}
+
+impl WireFormat {
+    pub fn new(encoding: Encoding, compression: Compression) -> Self {
+        WireFormat {
+            encoding,
+            compression,
+        }
+    }
+
+    // 압축하지 않은 json (이전 형식)
+    pub fn plain_json() -> Self {
+        WireFormat::new(Encoding::Json, Compression::Identity)
+    }
+
+    pub fn from_headers(
+        content_type: Option<&str>,
+        content_encoding: Option<&str>,
+    ) -> Result<Self, CodecError> {
+        Ok(WireFormat {
+            encoding: Encoding::from_content_type(content_type)?,
+            compression: Compression::from_content_encoding(content_encoding)?,
+        })
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, CodecError> {
+        let serialized = match self.encoding {
+            Encoding::Json => {
+                serde_json::to_vec(value).map_err(|e| CodecError::Serialize(e.to_string()))?
+            }
+            Encoding::Cbor => {
+                let mut serialized = Vec::new();
+                ciborium::ser::into_writer(value, &mut serialized)
+                    .map_err(|e| CodecError::Serialize(e.to_string()))?;
+                serialized
+            }
+        };
+
+        self.compression.compress(serialized)
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        let decompressed = self.compression.decompress(bytes)?;
+
+        match self.encoding {
+            Encoding::Json => serde_json::from_slice(&decompressed)
+                .map_err(|e| CodecError::Deserialize(e.to_string())),
+            Encoding::Cbor => ciborium::de::from_reader(decompressed.as_slice())
+                .map_err(|e| CodecError::Deserialize(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::file_sys::{scan_test_dir, FileSystem};
+    use super::*;
+    use std::fs;
+
+    fn scan(test_dir: &str) -> FileSystem {
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        for idx in 0..50 {
+            fs::write(format!("{}/sub/file_{}.txt", test_dir, idx), b"xilers").unwrap();
+        }
+
+        scan_test_dir(test_dir)
+    }
+
+    #[test]
+    fn test_wire_format_round_trip() {
+        let file_system = scan("test_root_codec_round_trip");
+        let json_len = WireFormat::plain_json().encode(&file_system).unwrap().len();
+
+        for encoding in [Encoding::Json, Encoding::Cbor] {
+            for compression in [Compression::Identity, Compression::Gzip, Compression::Zstd] {
+                let wire_format = WireFormat::new(encoding, compression);
+                let encoded = wire_format.encode(&file_system).unwrap();
+                let decoded: FileSystem = wire_format.decode(&encoded).unwrap();
+
+                assert!(file_system.diff(&decoded).is_empty());
+                assert_eq!(decoded.node.file_name(), file_system.node.file_name());
+                if compression != Compression::Identity {
+                    assert!(encoded.len() < json_len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_wire_format_from_headers() {
+        assert_eq!(
+            WireFormat::from_headers(None, None).unwrap(),
+            WireFormat::plain_json()
+        );
+        assert_eq!(
+            WireFormat::from_headers(Some("application/cbor"), Some("zstd")).unwrap(),
+            WireFormat::new(Encoding::Cbor, Compression::Zstd)
+        );
+        assert_eq!(
+            WireFormat::from_headers(Some("application/json; charset=utf-8"), Some("GZIP"))
+                .unwrap(),
+            WireFormat::new(Encoding::Json, Compression::Gzip)
+        );
+        assert!(WireFormat::from_headers(Some("application/xml"), None).is_err());
+        assert!(WireFormat::from_headers(None, Some("br")).is_err());
+    }
+}
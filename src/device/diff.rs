@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::file_sys::{FileKind, FileNode, FileSystem};
+
+// 경로는 모두 root 기준 상대경로이며 '/'로 구분
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FsChange {
+    Added { path: String, kind: FileKind },
+    Removed { path: String, kind: FileKind },
+    Modified { path: String },
+    Moved { from: String, to: String },
+}
+
+impl FsChange {
+    pub fn path(&self) -> &str {
+        match self {
+            FsChange::Added { path, .. } => path,
+            FsChange::Removed { path, .. } => path,
+            FsChange::Modified { path } => path,
+            FsChange::Moved { to, .. } => to,
+        }
+    }
+}
+
+impl std::fmt::Display for FsChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FsChange::Added { path, .. } => write!(f, "+ {}", path),
+            FsChange::Removed { path, .. } => write!(f, "- {}", path),
+            FsChange::Modified { path } => write!(f, "~ {}", path),
+            FsChange::Moved { from, to } => write!(f, "> {} -> {}", from, to),
+        }
+    }
+}
+
+impl FileSystem {
+    // self(이전 상태)에서 other(이후 상태)가 되기까지의 변경 목록
+    pub fn diff(&self, other: &FileSystem) -> Vec<FsChange> {
+        diff_nodes(&self.node, &other.node, "")
+    }
+}
+
+// before와 after를 같은 위치(base_path)의 node로 보고 하위 entry들을 비교
+// base_path는 before/after 자신의 상대경로 (root이면 빈 문자열)
+pub fn diff_nodes(before: &FileNode, after: &FileNode, base_path: &str) -> Vec<FsChange> {
+    let mut changes: Vec<FsChange> = Vec::new();
+    if is_same_subtree(before, after) {
+        return changes;
+    }
+
+    let mut removed: Vec<(String, &FileNode)> = Vec::new();
+    let mut added: Vec<(String, &FileNode)> = Vec::new();
+
+    compare_children(
+        before,
+        after,
+        base_path,
+        &mut changes,
+        &mut removed,
+        &mut added,
+    );
+
+    // 내용(hash)이 같은 entry가 한쪽에서 사라지고 다른 쪽에 생겼으면 이동으로 간주
+    for (removed_path, removed_node) in removed {
+        let moved_idx = added.iter().position(|(_, added_node)| {
+            added_node.kind() == removed_node.kind()
+                && added_node.hash().is_some()
+                && added_node.hash() == removed_node.hash()
+        });
+
+        match moved_idx {
+            Some(idx) => {
+                let (added_path, _) = added.remove(idx);
+                changes.push(FsChange::Moved {
+                    from: removed_path,
+                    to: added_path,
+                });
+            }
+            None => changes.push(FsChange::Removed {
+                path: removed_path,
+                kind: removed_node.kind(),
+            }),
+        }
+    }
+
+    for (added_path, added_node) in added {
+        changes.push(FsChange::Added {
+            path: added_path,
+            kind: added_node.kind(),
+        });
+    }
+
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+    changes
+}
+
+fn compare_children<'a>(
+    before: &'a FileNode,
+    after: &'a FileNode,
+    base_path: &str,
+    changes: &mut Vec<FsChange>,
+    removed: &mut Vec<(String, &'a FileNode)>,
+    added: &mut Vec<(String, &'a FileNode)>,
+) {
+    // 이름으로 찾으므로 entry가 많은 directory에서도 하위 entry 수에 비례
+    let after_children: HashMap<&str, &FileNode> = after
+        .children()
+        .iter()
+        .map(|child| (child.file_name(), child))
+        .collect();
+
+    for before_child in before.children() {
+        let child_path = join_path(base_path, before_child.file_name());
+        let after_child = after_children.get(before_child.file_name()).copied();
+
+        match after_child {
+            None => removed.push((child_path, before_child)),
+            Some(after_child) if after_child.kind() != before_child.kind() => {
+                removed.push((child_path.clone(), before_child));
+                added.push((child_path, after_child));
+            }
+            // Merkle digest가 같으면 하위 entry를 비교하지 않음
+            Some(after_child) if is_same_subtree(before_child, after_child) => {}
+            // 한쪽이라도 아직 탐색하지 않은 directory는 하위 entry를 비교할 수 없음
+            Some(after_child)
+                if after_child.is_expanded() == false || before_child.is_expanded() == false => {}
+            Some(after_child) if after_child.kind() == FileKind::Directory => {
+                compare_children(
+                    before_child,
+                    after_child,
+                    &child_path,
+                    changes,
+                    removed,
+                    added,
+                );
+            }
+            Some(after_child) => {
+                if is_modified(before_child, after_child) {
+                    changes.push(FsChange::Modified { path: child_path });
+                }
+            }
+        }
+    }
+
+    let before_names: HashSet<&str> = before
+        .children()
+        .iter()
+        .map(|child| child.file_name())
+        .collect();
+    for after_child in after.children() {
+        if before_names.contains(after_child.file_name()) == false {
+            added.push((join_path(base_path, after_child.file_name()), after_child));
+        }
+    }
+}
+
+fn is_same_subtree(before: &FileNode, after: &FileNode) -> bool {
+    before.kind() == FileKind::Directory
+        && after.kind() == FileKind::Directory
+        && before.hash().is_some()
+        && before.hash() == after.hash()
+}
+
+fn is_modified(before: &FileNode, after: &FileNode) -> bool {
+    match (before.hash(), after.hash()) {
+        (Some(before_hash), Some(after_hash)) => before_hash != after_hash,
+        _ => before.size() != after.size() || before.modified() != after.modified(),
+    }
+}
+
+fn join_path(base_path: &str, name: &str) -> String {
+    if base_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", base_path, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::file_sys::scan_test_dir;
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_diff_identical() {
+        let test_dir = "test_root_diff_identical";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/sub/a.txt", test_dir), b"a").unwrap();
+
+        let before = scan_test_dir(test_dir);
+        let after = scan_test_dir(test_dir);
+
+        assert!(before.diff(&after).is_empty());
+    }
+
+    #[test]
+    fn test_diff_changes() {
+        let test_dir = "test_root_diff_changes";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/keep.txt", test_dir), b"keep").unwrap();
+        fs::write(format!("{}/edit.txt", test_dir), b"before").unwrap();
+        fs::write(format!("{}/gone.txt", test_dir), b"gone").unwrap();
+        fs::write(format!("{}/move.txt", test_dir), b"move me").unwrap();
+
+        let before = scan_test_dir(test_dir);
+
+        fs::write(format!("{}/edit.txt", test_dir), b"after").unwrap();
+        fs::remove_file(format!("{}/gone.txt", test_dir)).unwrap();
+        fs::rename(
+            format!("{}/move.txt", test_dir),
+            format!("{}/sub/moved.txt", test_dir),
+        )
+        .unwrap();
+        fs::create_dir_all(format!("{}/new_dir", test_dir)).unwrap();
+
+        let after = scan_test_dir(test_dir);
+
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                FsChange::Modified {
+                    path: "edit.txt".to_string()
+                },
+                FsChange::Removed {
+                    path: "gone.txt".to_string(),
+                    kind: FileKind::File
+                },
+                FsChange::Added {
+                    path: "new_dir".to_string(),
+                    kind: FileKind::Directory
+                },
+                FsChange::Moved {
+                    from: "move.txt".to_string(),
+                    to: "sub/moved.txt".to_string()
+                },
+            ]
+        );
+    }
+}
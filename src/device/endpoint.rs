@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+
+use super::spec::DeviceSpec;
+
+// file transfer를 위해 peer가 연결을 시도할 주소의 종류, 우선순위가 높은 순서
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointKind {
+    Advertised, // config에 지정한 주소 (port forwarding, VPN 등)
+    Lan,        // network interface의 사설 주소
+    Public,     // network interface의 공인 주소
+    Observed,   // master가 본 요청의 주소, NAT 뒤의 peer끼리는 연결되지 않을 수 있음
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub host: String, // IP 주소 혹은 hostname, hostname은 연결하는 peer가 resolve (split-horizon, dynamic DNS)
+    pub port: u16,
+    pub kind: EndpointKind,
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.ip() {
+            Some(IpAddr::V6(_)) => write!(f, "[{}]:{}", self.host, self.port),
+            _ => write!(f, "{}:{}", self.host, self.port),
+        }
+    }
+}
+
+impl Endpoint {
+    pub fn new(addr: SocketAddr, kind: EndpointKind) -> Self {
+        Endpoint {
+            host: addr.ip().to_string(),
+            port: addr.port(),
+            kind,
+        }
+    }
+
+    // config의 "host" 혹은 "host:port", port가 없으면 default_port
+    pub fn advertised(advertise_addr: &str, default_port: u16) -> Option<Self> {
+        let advertise_addr = advertise_addr.trim();
+        if let Ok(addr) = advertise_addr.parse::<SocketAddr>() {
+            return Some(Endpoint::new(addr, EndpointKind::Advertised));
+        }
+        if let Ok(ip) = advertise_addr.parse::<IpAddr>() {
+            return Some(Endpoint::new(
+                SocketAddr::new(ip, default_port),
+                EndpointKind::Advertised,
+            ));
+        }
+
+        let (host, port) = match advertise_addr.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().ok()?),
+            None => (advertise_addr, default_port),
+        };
+        if host.is_empty() || host.contains(|c: char| c == ':' || c.is_whitespace()) {
+            return None;
+        }
+
+        Some(Endpoint {
+            host: host.to_string(),
+            port,
+            kind: EndpointKind::Advertised,
+        })
+    }
+
+    // hostname이면 None
+    pub fn ip(&self) -> Option<IpAddr> {
+        self.host.parse().ok()
+    }
+
+    // 연결할 주소, hostname은 이 device의 resolver로 확인하며 IPv4를 먼저 시도
+    pub fn resolve(&self) -> std::io::Result<Vec<SocketAddr>> {
+        let mut addrs: Vec<SocketAddr> =
+            (self.host.as_str(), self.port).to_socket_addrs()?.collect();
+        addrs.sort_by_key(|addr| addr.is_ipv6());
+        Ok(addrs)
+    }
+
+    fn is_ipv6(&self) -> bool {
+        self.ip().map(|ip| ip.is_ipv6()).unwrap_or(false)
+    }
+}
+
+// network interface의 주소를 후보의 종류로 분류
+// loopback, link-local 등 다른 device가 사용할 수 없는 주소는 None
+pub fn classify(ip: &IpAddr) -> Option<EndpointKind> {
+    if ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() {
+        return None;
+    }
+
+    match ip {
+        IpAddr::V4(ip) if ip.is_link_local() || ip.is_broadcast() => None,
+        IpAddr::V4(ip) if ip.is_private() || is_shared_v4(ip.octets()) => Some(EndpointKind::Lan),
+        IpAddr::V6(ip) if ip.segments()[0] & 0xffc0 == 0xfe80 => None, // link-local은 scope id가 필요
+        IpAddr::V6(ip) if ip.segments()[0] & 0xfe00 == 0xfc00 => Some(EndpointKind::Lan), // unique local
+        _ => Some(EndpointKind::Public),
+    }
+}
+
+// 100.64.0.0/10 (carrier-grade NAT, Tailscale 등)
+fn is_shared_v4(octets: [u8; 4]) -> bool {
+    octets[0] == 100 && octets[1] & 0xc0 == 64
+}
+
+// 요청을 보낸 client의 주소
+// 신뢰하는 proxy에서 온 요청이면 X-Forwarded-For를 오른쪽부터 읽어서 신뢰하는 proxy가 아닌 첫 주소를 사용
+pub fn observed_ip(
+    peer_ip: IpAddr,
+    forwarded_for: Option<&str>,
+    trusted_proxies: &[IpAddr],
+) -> IpAddr {
+    let forwarded_for = match forwarded_for {
+        Some(forwarded_for) if trusted_proxies.contains(&peer_ip) => forwarded_for,
+        _ => return peer_ip,
+    };
+
+    let mut client_ip = peer_ip;
+    for hop in forwarded_for.rsplit(',') {
+        let ip = match parse_forwarded_ip(hop.trim()) {
+            Some(ip) => ip,
+            None => break, // 형식이 잘못된 값 이후는 client가 임의로 넣었을 수 있음
+        };
+        client_ip = ip;
+        if trusted_proxies.contains(&ip) == false {
+            break;
+        }
+    }
+
+    client_ip
+}
+
+// "ip", "ip:port", "[ipv6]:port" 형식 허용
+fn parse_forwarded_ip(hop: &str) -> Option<IpAddr> {
+    hop.parse::<IpAddr>()
+        .ok()
+        .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+impl DeviceSpec {
+    // master가 본 주소를 후보에 추가, 이미 다른 종류로 있는 주소면 추가하지 않음
+    pub fn add_observed_endpoint(&mut self, ip: IpAddr) {
+        let listen_port = self.listen_port;
+        if self
+            .endpoints
+            .iter()
+            .any(|endpoint| endpoint.ip() == Some(ip) && endpoint.port == listen_port)
+            == false
+        {
+            self.endpoints.push(Endpoint::new(
+                SocketAddr::new(ip, listen_port),
+                EndpointKind::Observed,
+            ));
+        }
+    }
+
+    // 연결을 시도할 순서의 주소 목록
+    // 종류의 우선순위 순서이며, 같은 종류에서는 IPv4를 먼저 시도 (hostname은 resolve한 뒤 IPv4부터)
+    // 후보가 없는 이전 client는 master가 본 주소를 사용
+    pub fn connect_candidates(&self) -> Vec<Endpoint> {
+        let mut endpoints = self.endpoints.clone();
+        if let Ok(ip) = self.ip.parse::<IpAddr>() {
+            endpoints.push(Endpoint::new(
+                SocketAddr::new(ip, self.listen_port),
+                EndpointKind::Observed,
+            ));
+        }
+        endpoints.sort_by_key(|endpoint| (endpoint.kind, endpoint.is_ipv6()));
+
+        let mut candidates: Vec<Endpoint> = Vec::new();
+        for endpoint in endpoints {
+            let duplicated = candidates.iter().any(|candidate| {
+                candidate.host == endpoint.host && candidate.port == endpoint.port
+            });
+            if duplicated == false {
+                candidates.push(endpoint);
+            }
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(&ip("192.168.0.10")), Some(EndpointKind::Lan));
+        assert_eq!(classify(&ip("100.101.102.103")), Some(EndpointKind::Lan));
+        assert_eq!(classify(&ip("fd12:3456::1")), Some(EndpointKind::Lan));
+        assert_eq!(classify(&ip("203.0.113.7")), Some(EndpointKind::Public));
+        assert_eq!(classify(&ip("2001:db8::7")), Some(EndpointKind::Public));
+        assert_eq!(classify(&ip("127.0.0.1")), None);
+        assert_eq!(classify(&ip("::1")), None);
+        assert_eq!(classify(&ip("169.254.1.1")), None);
+        assert_eq!(classify(&ip("fe80::1")), None);
+    }
+
+    #[test]
+    fn test_observed_ip() {
+        let proxy = ip("10.0.0.1");
+        let trusted_proxies = [proxy, ip("10.0.0.2")];
+
+        // 신뢰하지 않는 주소에서 온 header는 무시
+        assert_eq!(
+            observed_ip(ip("198.51.100.4"), Some("203.0.113.7"), &trusted_proxies),
+            ip("198.51.100.4")
+        );
+        assert_eq!(observed_ip(proxy, Some("203.0.113.7"), &[]), proxy);
+
+        assert_eq!(
+            observed_ip(proxy, Some("203.0.113.7"), &trusted_proxies),
+            ip("203.0.113.7")
+        );
+        // client가 임의로 넣은 왼쪽 값은 사용하지 않음
+        assert_eq!(
+            observed_ip(
+                proxy,
+                Some("192.0.2.1, 203.0.113.7, 10.0.0.2"),
+                &trusted_proxies
+            ),
+            ip("203.0.113.7")
+        );
+        assert_eq!(
+            observed_ip(proxy, Some("[2001:db8::7]:4711"), &trusted_proxies),
+            ip("2001:db8::7")
+        );
+        assert_eq!(observed_ip(proxy, Some("unknown"), &trusted_proxies), proxy);
+        assert_eq!(observed_ip(proxy, None, &trusted_proxies), proxy);
+    }
+
+    #[test]
+    fn test_connect_candidates() {
+        let mut spec = DeviceSpec {
+            ip: "198.51.100.4".to_string(),
+            listen_port: 8081,
+            endpoints: vec![
+                Endpoint::new("203.0.113.7:8081".parse().unwrap(), EndpointKind::Public),
+                Endpoint::new("[fd12::5]:8081".parse().unwrap(), EndpointKind::Lan),
+                Endpoint::new("192.168.0.10:8081".parse().unwrap(), EndpointKind::Lan),
+                Endpoint::advertised("nas.example.com:9000", 8081).unwrap(),
+            ],
+            ..DeviceSpec::default()
+        };
+        spec.add_observed_endpoint(ip("203.0.113.7"));
+        spec.add_observed_endpoint(ip("198.51.100.4"));
+
+        let candidates: Vec<String> = spec
+            .connect_candidates()
+            .iter()
+            .map(|endpoint| endpoint.to_string())
+            .collect();
+        assert_eq!(
+            candidates,
+            vec![
+                "nas.example.com:9000",
+                "192.168.0.10:8081",
+                "[fd12::5]:8081",
+                "203.0.113.7:8081",
+                "198.51.100.4:8081",
+            ]
+        );
+
+        let legacy = DeviceSpec {
+            ip: "192.168.0.20".to_string(),
+            listen_port: 8081,
+            ..DeviceSpec::default()
+        };
+        assert_eq!(
+            legacy.connect_candidates(),
+            vec![Endpoint::new(
+                "192.168.0.20:8081".parse().unwrap(),
+                EndpointKind::Observed
+            )]
+        );
+    }
+
+    #[test]
+    fn test_advertised_endpoint() {
+        let advertised = |addr: &str| Endpoint::advertised(addr, 8081).map(|e| e.to_string());
+
+        // hostname은 resolve하지 않고 그대로 전달
+        assert_eq!(
+            advertised("nas.example.com"),
+            Some("nas.example.com:8081".to_string())
+        );
+        assert_eq!(
+            advertised("nas.example.com:18081"),
+            Some("nas.example.com:18081".to_string())
+        );
+        assert_eq!(advertised("192.0.2.9"), Some("192.0.2.9:8081".to_string()));
+        assert_eq!(
+            advertised("2001:db8::7"),
+            Some("[2001:db8::7]:8081".to_string())
+        );
+        assert_eq!(
+            advertised("[2001:db8::7]:9000"),
+            Some("[2001:db8::7]:9000".to_string())
+        );
+        assert_eq!(advertised("nas.example.com:port"), None);
+        assert_eq!(advertised(""), None);
+
+        let localhost = Endpoint::advertised("127.0.0.1:9000", 8081).unwrap();
+        assert_eq!(
+            localhost.resolve().unwrap(),
+            vec!["127.0.0.1:9000".parse::<SocketAddr>().unwrap()]
+        );
+    }
+}
@@ -1,50 +1,57 @@
 use serde::{Deserialize, Serialize};
 use std::borrow::{Borrow, BorrowMut};
 
-use std::collections::VecDeque;
+use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+use super::diff::{diff_nodes, FsChange};
+use super::hash::{self, HashCache};
+use super::ignore_rules::IgnoreRules;
+use super::render::TreeRenderer;
+use super::share_policy::{SharePolicies, SharePolicy};
+use icu_collator::{Collator, CollatorOptions, Strength};
+use ignore::gitignore::Gitignore;
+
+thread_local! {
+    // locale에 관계없는 Unicode 기본 정렬(root collation), secondary strength이므로 대소문자는 구분하지 않음
+    // canonical equivalence를 따르므로 NFC와 NFD 이름은 같은 순서
+    static NAME_COLLATOR: Collator = {
+        let mut options = CollatorOptions::new();
+        options.strength = Some(Strength::Secondary);
+        Collator::try_new(&Default::default(), options).unwrap()
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FileKind {
+    File,
+    Directory,
+    Symlink,
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct FileNode {
     file_name: String,
+    kind: FileKind,
+    size: u64,                   // byte 단위
+    modified: Option<u64>,       // UNIX epoch 기준 초, 플랫폼에 따라 없을 수 있음
+    permissions: u32,            // unix mode bit (0o7777), 그 외 플랫폼은 readonly 여부로 근사
+    hash: Option<String>, // 파일은 내용의 BLAKE3 digest, directory는 하위 entry의 Merkle digest, symlink는 None
+    link_target: Option<String>, // symlink인 경우 link가 가리키는 경로
+    #[serde(default)]
+    unexpanded: bool, // 탐색 깊이 제한으로 하위 entry를 아직 탐색하지 않은 directory
+    #[serde(default, skip_serializing_if = "SharePolicy::is_downloadable")]
+    share: SharePolicy, // master에 등록된 tree에서만 의미가 있음
     children: Vec<FileNode>,
 }
 
 impl std::fmt::Debug for FileNode {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut deque: VecDeque<(FileNode, usize, usize)> = VecDeque::new();
-        deque.push_front((self.clone(), 0, 0));
-        let _ = write!(f, "\n");
-
-        while deque.is_empty() != true {
-            let opt = deque.pop_back().unwrap();
-            let file_node = opt.0;
-            let indent = opt.1;
-            let child = file_node.children;
-
-            let formatted_str: String;
-            // TODO: fs::metadata 이용해서 변경하기
-            if child.len() >= 1 {
-                formatted_str =
-                    format!("{}{}/\n", " ".repeat((indent + 1) * 4), file_node.file_name);
-            } else {
-                formatted_str =
-                    format!("{}{}\n", " ".repeat((indent + 1) * 4), file_node.file_name);
-            }
-
-            let _ = write!(f, "{}", formatted_str);
-
-            for (idx, node) in child.iter().enumerate() {
-                if idx >= 7 {
-                    // 숫자 변경 필요
-                    break;
-                }
-                deque.push_back((node.clone(), indent + 1, idx));
-            }
-        }
-
-        Ok(())
+        write!(f, "\n{}", TreeRenderer::default().render(self))
     }
 }
 
@@ -60,31 +67,370 @@ impl FileNode {
                 return None;
             }
 
-            Some(FileNode {
-                file_name: file_name.to_string(),
-                children: Vec::new(),
-            })
+            let mut root_node = FileNode::empty(file_name);
+            // 사용자가 직접 지정한 root는 symlink여도 따라감
+            if let Ok(metadata) = fs::metadata(file_name) {
+                root_node.set_metadata(&metadata);
+            }
+
+            Some(root_node)
         } else {
-            Some(FileNode {
-                file_name: file_name.to_string(),
-                children: Vec::new(),
-            })
+            Some(FileNode::empty(file_name))
+        }
+    }
+
+    fn empty(file_name: &str) -> Self {
+        FileNode {
+            file_name: file_name.to_string(),
+            kind: FileKind::File,
+            size: 0,
+            modified: None,
+            permissions: 0,
+            hash: None,
+            link_target: None,
+            unexpanded: false,
+            share: SharePolicy::default(),
+            children: Vec::new(),
+        }
+    }
+
+    // 실제 경로가 없는 directory (여러 device의 tree를 묶을 때 사용)
+    pub(crate) fn virtual_dir(file_name: &str) -> Self {
+        let mut node = FileNode::empty(file_name);
+        node.kind = FileKind::Directory;
+        node
+    }
+
+    pub(crate) fn rename(&mut self, file_name: String) {
+        self.file_name = file_name;
+    }
+
+    pub(crate) fn children_mut(&mut self) -> &mut Vec<FileNode> {
+        &mut self.children
+    }
+
+    pub(crate) fn set_unexpanded(&mut self, unexpanded: bool) {
+        self.unexpanded = unexpanded;
+    }
+
+    pub(crate) fn set_share_policy(&mut self, share: SharePolicy) {
+        self.share = share;
+    }
+
+    // 하위 entry가 바뀐 directory의 digest를 다시 계산 (탐색하지 않은 directory는 None)
+    pub(crate) fn update_merkle_digest(&mut self) {
+        if self.kind != FileKind::Directory {
+            return;
         }
+        self.hash = match self.unexpanded {
+            true => None,
+            false => Some(self.merkle_digest()),
+        };
+    }
+
+    // 하위 entry의 종류, 이름, digest로 계산하므로 하위 entry가 같으면 directory의 이름이나 위치와 관계없이 같은 값
+    fn merkle_digest(&self) -> String {
+        let mut entries: Vec<u8> = Vec::new();
+        for child in &self.children {
+            let content = match (child.kind, &child.hash) {
+                (FileKind::Symlink, _) => child.link_target.clone().unwrap_or_default(),
+                (_, Some(hash)) => hash.clone(),
+                // hash를 계산하지 못한 파일과 아직 탐색하지 않은 directory
+                (_, None) => format!("{}:{:?}", child.size, child.modified),
+            };
+            entries.extend_from_slice(
+                format!(
+                    "{:?}:{}:{}:{}\n",
+                    child.kind,
+                    child.file_name.len(),
+                    child.file_name,
+                    content
+                )
+                .as_bytes(),
+            );
+        }
+
+        hash::hash_bytes(&entries)
     }
 
     pub fn add_child(&mut self, child: FileNode) {
         self.children.push(child);
     }
 
+    // directory가 먼저 오고, 이름은 Unicode collation으로 대소문자를 구분하지 않고 비교 (e.g. "a1" < "Ärger" < "b")
+    // collation이 같은 이름(대소문자만 다른 이름 등)은 원래 이름의 codepoint로 비교해서 항상 같은 순서를 보장
+    pub fn canonical_order(a: &FileNode, b: &FileNode) -> std::cmp::Ordering {
+        let a_is_dir = a.kind == FileKind::Directory;
+        let b_is_dir = b.kind == FileKind::Directory;
+
+        b_is_dir
+            .cmp(&a_is_dir)
+            .then_with(|| {
+                NAME_COLLATOR.with(|collator| collator.compare(&a.file_name, &b.file_name))
+            })
+            .then_with(|| a.file_name.cmp(&b.file_name))
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn kind(&self) -> FileKind {
+        self.kind
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == FileKind::Directory
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn modified(&self) -> Option<u64> {
+        self.modified
+    }
+
+    pub fn permissions(&self) -> u32 {
+        self.permissions
+    }
+
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    pub fn link_target(&self) -> Option<&str> {
+        self.link_target.as_deref()
+    }
+
+    pub fn share_policy(&self) -> SharePolicy {
+        self.share
+    }
+
+    pub fn is_expanded(&self) -> bool {
+        self.unexpanded == false
+    }
+
+    // 하위에 아직 탐색하지 않은 directory가 있는지 여부
+    pub fn has_unexpanded(&self) -> bool {
+        self.unexpanded || self.children.iter().any(|child| child.has_unexpanded())
+    }
+
+    pub fn children(&self) -> &[FileNode] {
+        &self.children
+    }
+
+    fn set_metadata(&mut self, metadata: &fs::Metadata) {
+        let file_type = metadata.file_type();
+        self.kind = if file_type.is_symlink() {
+            FileKind::Symlink
+        } else if file_type.is_dir() {
+            FileKind::Directory
+        } else {
+            FileKind::File
+        };
+        self.size = if self.kind == FileKind::File {
+            metadata.len()
+        } else {
+            0
+        };
+        if self.kind != FileKind::File {
+            self.hash = None;
+        }
+        self.modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_secs());
+        self.permissions = FileNode::permission_bits(metadata);
+    }
+
+    #[cfg(unix)]
+    fn permission_bits(metadata: &fs::Metadata) -> u32 {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o7777
+    }
+
+    #[cfg(not(unix))]
+    fn permission_bits(metadata: &fs::Metadata) -> u32 {
+        if metadata.permissions().readonly() {
+            0o444
+        } else {
+            0o666
+        }
+    }
+
     fn is_exist(path_str: &str) -> bool {
         let _path = Path::new(path_str);
         _path.exists()
     }
 }
 
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit_idx = 0;
+    while size >= 1024.0 && unit_idx < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_idx += 1;
+    }
+
+    if unit_idx == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit_idx])
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymlinkPolicy {
+    Skip, // tree에 포함하지 않음
+    #[default]
+    Record, // link 자체만 기록하고 따라가지 않음
+    FollowWithinRoot, // root 안을 가리키는 경우에만 따라감
+}
+
+// directory를 식별하는 값, 같은 directory를 다시 방문하는지(순환) 확인하는데 사용
+#[cfg(unix)]
+type DirKey = (u64, u64); // (device, inode)
+#[cfg(not(unix))]
+type DirKey = PathBuf; // canonicalize된 경로
+
+#[cfg(unix)]
+fn dir_key(path: &Path) -> Option<DirKey> {
+    use std::os::unix::fs::MetadataExt;
+    fs::metadata(path)
+        .ok()
+        .map(|metadata| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn dir_key(path: &Path) -> Option<DirKey> {
+    fs::canonicalize(path).ok()
+}
+
+// 병렬 탐색의 진행 상황을 UI에 전달하고, 탐색을 중단시키기 위해 공유
+#[derive(Default)]
+pub struct ScanProgress {
+    scanned_entries: AtomicUsize,
+    cancelled: Arc<AtomicBool>, // signal handler 등에 그대로 등록할 수 있도록 Arc로 보관
+}
+
+impl ScanProgress {
+    pub fn new() -> Self {
+        ScanProgress::default()
+    }
+
+    pub fn scanned_entries(&self) -> usize {
+        self.scanned_entries.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancelled)
+    }
+}
+
+// group에 공유할 수 있는 운영체제
+const SUPPORTED_OS: [&str; 5] = ["windows", "macos", "linux", "android", "ios"];
+
+#[derive(Debug)]
+pub enum FsError {
+    RootNotFound(PathBuf),
+    PermissionDenied(PathBuf),
+    UnsupportedPlatform(String),
+    PartialScan(Vec<PathBuf>), // 읽지 못한 경로를 제외하고 tree를 구성함
+    Cancelled,                 // 이전 tree를 유지함
+    Io(PathBuf, std::io::Error),
+}
+
+impl std::fmt::Display for FsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FsError::RootNotFound(path) => write!(f, "root 경로({:?})가 존재하지 않습니다.", path),
+            FsError::PermissionDenied(path) => {
+                write!(f, "root 경로({:?})를 읽을 권한이 없습니다.", path)
+            }
+            FsError::UnsupportedPlatform(os) => write!(f, "지원하지 않는 운영체제입니다: {}", os),
+            FsError::PartialScan(paths) => {
+                write!(f, "{}개의 경로를 읽지 못하고 제외했습니다.", paths.len())
+            }
+            FsError::Cancelled => write!(f, "탐색이 취소되었습니다."),
+            FsError::Io(path, e) => write!(f, "root 경로({:?})를 확인하지 못했습니다: {}", path, e),
+        }
+    }
+}
+
+impl std::error::Error for FsError {}
+
+// root 기준 경로(path)의 subtree를 새 node로 교체하기 위한 변경 단위
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SubtreeUpdate {
+    pub path: Vec<String>, // 비어있으면 root
+    pub node: FileNode,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct FileSystem {
     pub node: FileNode, // 결정된 root node
+    #[serde(skip)]
+    hash_cache: HashCache, // 재탐색시 변경되지 않은 파일의 hash 재계산 방지
+    #[serde(skip)]
+    ignore_rules: IgnoreRules, // 무시되는 entry는 tree에 포함하지 않음
+    #[serde(skip)]
+    symlink_policy: SymlinkPolicy,
+    #[serde(skip)]
+    scan_depth: Option<usize>, // 한번에 탐색할 최대 깊이, None이면 제한 없음
+    #[serde(skip)]
+    share_policies: SharePolicies,
+}
+
+// build_tree 도중 공유되는 상태, 병렬 탐색시에는 하위 directory마다 복제됨
+#[derive(Clone)]
+struct ScanContext<'a> {
+    hash_cache: &'a HashCache,
+    ignore_rules: &'a IgnoreRules,
+    ignore_stack: Vec<Gitignore>, // root부터 현재 directory까지의 .xilersignore
+    symlink_policy: SymlinkPolicy,
+    canonical_root: Option<PathBuf>,
+    ancestors: Vec<DirKey>, // root부터 현재 directory까지, 순환 감지용
+    max_depth: Option<usize>,
+    depth: usize, // 탐색을 시작한 node 기준 현재 깊이
+    progress: Option<&'a ScanProgress>,
+    parallel: bool,
+    unreadable: &'a Mutex<Vec<PathBuf>>, // 권한 등의 문제로 읽지 못한 경로
+}
+
+fn is_within(canonical_root: Option<&Path>, path: &Path) -> bool {
+    match (canonical_root, fs::canonicalize(path)) {
+        (Some(canonical_root), Ok(canonical_path)) => canonical_path.starts_with(canonical_root),
+        _ => false,
+    }
+}
+
+impl ScanContext<'_> {
+    fn is_within_root(&self, path: &Path) -> bool {
+        is_within(self.canonical_root.as_deref(), path)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.progress
+            .map(|progress| progress.is_cancelled())
+            .unwrap_or(false)
+    }
+
+    fn mark_unreadable(&self, path: &Path) {
+        self.unreadable.lock().unwrap().push(path.to_path_buf());
+    }
 }
 
 impl std::fmt::Debug for FileSystem {
@@ -93,68 +439,511 @@ impl std::fmt::Debug for FileSystem {
     }
 }
 
-// TODO: unwrap 처리
 impl FileSystem {
-    pub fn new(root_path: &mut str) -> Self {
-        // 운영체제에 따라 기본 root_path 설정
-        if FileNode::is_exist(root_path) != true {
-            println!(
-                "root_path: {:?}가 존재하지 않습니다. 운영체제별 Default path로 설정합니다.",
-                root_path
-            );
-            let default_root_path = match std::env::consts::OS {
-                "windows" => "C:\\Users\\Public",
-                "macos" => "/Users/Shared",
-                "linux" => "/home",
-                "android" => "/sdcard",
-                "ios" => "/var/mobile",
-                _ => panic!("지원하지 않는 운영체제입니다. 프로그램을 종료합니다."),
-            };
+    // root_path가 없거나 읽을 수 없으면 다른 경로로 대체하지 않고 Err
+    pub fn new(root_path: &str) -> Result<Self, FsError> {
+        let os = std::env::consts::OS;
+        if SUPPORTED_OS.contains(&os) == false {
+            return Err(FsError::UnsupportedPlatform(os.to_string()));
+        }
 
-            let file_node = FileNode::new(default_root_path, true).expect(&format!(
-                "파일시스템 생성시 문제가 발생했습니다. root_path({:?})를 참고해주십시오.",
-                default_root_path
-            ));
+        let path = PathBuf::from(root_path);
+        let metadata = fs::metadata(&path).map_err(|e| FileSystem::root_error(&path, e))?;
+        if metadata.is_dir() {
+            fs::read_dir(&path).map_err(|e| FileSystem::root_error(&path, e))?;
+        }
 
-            FileSystem { node: file_node }
-        } else {
-            let file_node = FileNode::new(root_path, true).expect(&format!(
-                "파일시스템 생성시 문제가 발생했습니다. root_path({:?})를 참고해주십시오.",
-                root_path
-            ));
-            FileSystem { node: file_node }
+        let file_node = FileNode::new(root_path, true).ok_or(FsError::RootNotFound(path))?;
+        Ok(FileSystem {
+            ignore_rules: IgnoreRules::new(Path::new(&file_node.file_name), &[]),
+            node: file_node,
+            hash_cache: HashCache::new(),
+            symlink_policy: SymlinkPolicy::default(),
+            scan_depth: None,
+            share_policies: SharePolicies::default(),
+        })
+    }
+
+    fn root_error(path: &Path, e: std::io::Error) -> FsError {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => FsError::RootNotFound(path.to_path_buf()),
+            std::io::ErrorKind::PermissionDenied => FsError::PermissionDenied(path.to_path_buf()),
+            _ => FsError::Io(path.to_path_buf(), e),
+        }
+    }
+
+    // client config의 전역 ignore 목록 설정, 다음 탐색부터 적용
+    pub fn set_ignore_patterns(&mut self, patterns: &[String]) {
+        self.ignore_rules = IgnoreRules::new(Path::new(&self.node.file_name), patterns);
+    }
+
+    // 다음 탐색부터 적용
+    pub fn set_symlink_policy(&mut self, symlink_policy: SymlinkPolicy) {
+        self.symlink_policy = symlink_policy;
+    }
+
+    // 다음 탐색부터 적용, 더 깊은 directory는 unexpanded로 표시되고 expand_subtree로 탐색
+    pub fn set_scan_depth(&mut self, scan_depth: Option<usize>) {
+        self.scan_depth = scan_depth;
+    }
+
+    // tree는 그대로 두고 master에 등록할 때 적용
+    pub fn set_share_policies(&mut self, share_policies: SharePolicies) {
+        self.share_policies = share_policies;
+    }
+
+    pub(crate) fn share_policies(&self) -> &SharePolicies {
+        &self.share_policies
+    }
+
+    // root 아래의 path가 ignore rule에 의해 공유되지 않는지 여부
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.ignore_rules.is_ignored(path)
+    }
+
+    // 읽지 못한 경로가 있으면 해당 경로를 제외한 tree를 구성하고 PartialScan을 반환
+    pub fn init_file_node(&mut self) -> Result<(), FsError> {
+        self.scan_root(None, false)
+    }
+
+    // 하위 directory들을 여러 thread에서 나눠서 탐색하며, 결과는 init_file_node와 동일
+    // 중간에 취소되면 이전 tree를 유지하고 Cancelled를 반환
+    pub fn init_file_node_parallel(&mut self, progress: &ScanProgress) -> Result<(), FsError> {
+        let before_node = self.node.clone();
+        let scan_result = self.scan_root(Some(progress), true);
+
+        if progress.is_cancelled() {
+            self.node = before_node;
+            return Err(FsError::Cancelled);
         }
+        scan_result
     }
 
-    pub fn init_file_node(&mut self) {
+    fn scan_root(
+        &mut self,
+        progress: Option<&ScanProgress>,
+        parallel: bool,
+    ) -> Result<(), FsError> {
         let file_name = self.node.file_name.clone();
         let root_path = Path::new(&file_name);
 
-        FileSystem::build_tree(&mut self.node, root_path);
+        // 재탐색하는 경우 이전 tree를 버리고 새로 구성
+        self.node.children.clear();
+        if let Ok(metadata) = fs::metadata(root_path) {
+            self.node.set_metadata(&metadata);
+        }
+
+        let unreadable = Mutex::new(Vec::new());
+        let mut scan_context = ScanContext {
+            hash_cache: &self.hash_cache,
+            ignore_rules: &self.ignore_rules,
+            ignore_stack: Vec::new(),
+            symlink_policy: self.symlink_policy,
+            canonical_root: fs::canonicalize(root_path).ok(),
+            ancestors: Vec::new(),
+            max_depth: self.scan_depth,
+            depth: 0,
+            progress,
+            parallel,
+            unreadable: &unreadable,
+        };
+        FileSystem::fill_hash(&mut self.node, root_path, &scan_context);
+        FileSystem::build_tree(&mut self.node, root_path, &mut scan_context);
+
+        let mut unreadable = unreadable.into_inner().unwrap();
+        if unreadable.is_empty() {
+            return Ok(());
+        }
+        unreadable.sort();
+        Err(FsError::PartialScan(unreadable))
+    }
+
+    fn find_node_mut<'a>(
+        node: &'a mut FileNode,
+        components: &[String],
+    ) -> Option<&'a mut FileNode> {
+        let mut current_node = node;
+        for name in components {
+            current_node = current_node
+                .children
+                .iter_mut()
+                .find(|child| &child.file_name == name)?;
+        }
+
+        Some(current_node)
+    }
+
+    fn components_of(relative_path: &Path) -> Vec<String> {
+        relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    // components에 해당하는 directory를 다시 탐색하고 탐색 전의 node를 반환
+    // tree 혹은 disk에 해당 directory가 없으면 None
+    fn rescan_node(&mut self, components: &[String]) -> Option<FileNode> {
+        let root_path = PathBuf::from(&self.node.file_name);
+        let abs_path = components
+            .iter()
+            .fold(root_path.clone(), |path, name| path.join(name));
+        if abs_path.is_dir() == false {
+            return None;
+        }
+
+        let canonical_root = fs::canonicalize(&root_path).ok();
+        let ancestors: Vec<DirKey> = (0..components.len())
+            .filter_map(|depth| {
+                let ancestor_path = components[..depth]
+                    .iter()
+                    .fold(root_path.clone(), |path, name| path.join(name));
+                dir_key(&ancestor_path)
+            })
+            .collect();
+
+        let node = FileSystem::find_node_mut(&mut self.node, components)?;
+        // symlink는 탐색할 때와 같은 조건일 때만 따라감 (root 밖이나 순환하는 link는 기록만 함)
+        if components.is_empty() == false && node.link_target.is_some() {
+            let followable = self.symlink_policy == SymlinkPolicy::FollowWithinRoot
+                && is_within(canonical_root.as_deref(), &abs_path)
+                && dir_key(&abs_path).map_or(true, |key| ancestors.contains(&key) == false);
+            if followable == false {
+                return None;
+            }
+        }
+        let before_node = node.clone();
+
+        node.children.clear();
+        node.unexpanded = false;
+        // root와 따라간 symlink는 가리키는 directory의 metadata 사용
+        let metadata = if components.is_empty() || node.link_target.is_some() {
+            fs::metadata(&abs_path)
+        } else {
+            fs::symlink_metadata(&abs_path)
+        };
+        if let Ok(metadata) = metadata {
+            node.set_metadata(&metadata);
+        }
+
+        // 상위 directory들의 .xilersignore도 적용
+        let ignore_stack = match abs_path.parent() {
+            Some(parent_path) if components.is_empty() == false => {
+                self.ignore_rules.load_ancestors(parent_path)
+            }
+            _ => Vec::new(),
+        };
+        let unreadable = Mutex::new(Vec::new());
+        let mut scan_context = ScanContext {
+            hash_cache: &self.hash_cache,
+            ignore_rules: &self.ignore_rules,
+            ignore_stack,
+            symlink_policy: self.symlink_policy,
+            canonical_root,
+            ancestors,
+            max_depth: self.scan_depth,
+            depth: 0,
+            progress: None,
+            parallel: false,
+            unreadable: &unreadable,
+        };
+        FileSystem::build_tree(node, &abs_path, &mut scan_context);
+        for path in unreadable.into_inner().unwrap() {
+            log::warn!("읽지 못한 경로를 제외했습니다: {:?}", path);
+        }
+        self.update_ancestor_hashes(components);
+
+        Some(before_node)
     }
 
-    fn build_tree(current_node: &mut FileNode, path: &Path) {
-        if path.is_dir() {
-            if let Ok(entries) = fs::read_dir(path) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        let path = entry.path();
-                        let file_name = entry.file_name().to_string_lossy().into_owned();
+    // components에 해당하는 node가 바뀐 뒤 root까지의 Merkle digest를 다시 계산
+    fn update_ancestor_hashes(&mut self, components: &[String]) {
+        for depth in (0..components.len()).rev() {
+            if let Some(node) = FileSystem::find_node_mut(&mut self.node, &components[..depth]) {
+                node.update_merkle_digest();
+            }
+        }
+    }
 
-                        let mut child_node = FileNode::new(&file_name, false).unwrap();
+    // relative_path에 해당하는 directory를 다시 탐색하고, master에 전달할 변경분을 반환 (변경이 없으면 None)
+    // 해당 directory가 tree 혹은 disk에 없으면 존재하는 가장 가까운 상위 directory를 다시 탐색
+    pub fn refresh_subtree(&mut self, relative_path: &Path) -> Option<SubtreeUpdate> {
+        let mut components = FileSystem::components_of(relative_path);
 
-                        FileSystem::build_tree(&mut child_node, &path);
+        loop {
+            // 아직 탐색하지 않은 영역의 변경은 expand_subtree 때 반영
+            if let Some(node) = FileSystem::find_node_mut(&mut self.node, &components) {
+                if node.unexpanded {
+                    return None;
+                }
+            }
 
-                        current_node.add_child(child_node);
+            if let Some(before_node) = self.rescan_node(&components) {
+                let node = FileSystem::find_node_mut(&mut self.node, &components).unwrap();
+                if diff_nodes(&before_node, node, &components.join("/")).is_empty() {
+                    return None;
+                }
+
+                return Some(SubtreeUpdate {
+                    path: components,
+                    node: node.clone(),
+                });
+            }
+
+            if components.pop().is_none() {
+                log::warn!("root({:?})를 다시 탐색할 수 없습니다.", self.node.file_name);
+                return None;
+            }
+        }
+    }
+
+    // unexpanded로 표시된 directory를 scan_depth만큼 탐색해서 master에 전달할 변경분을 반환
+    pub fn expand_subtree(&mut self, relative_path: &Path) -> Option<SubtreeUpdate> {
+        let components = FileSystem::components_of(relative_path);
+        self.rescan_node(&components)?;
+
+        let node = FileSystem::find_node_mut(&mut self.node, &components).unwrap();
+        Some(SubtreeUpdate {
+            path: components,
+            node: node.clone(),
+        })
+    }
+
+    // 적용된 변경 목록을 반환하며, 경로에 해당하는 node가 없으면 None
+    pub fn apply_subtree_update(&mut self, update: SubtreeUpdate) -> Option<Vec<FsChange>> {
+        let is_root = update.path.is_empty();
+        let base_path = update.path.join("/");
+        let node = FileSystem::find_node_mut(&mut self.node, &update.path)?;
+
+        let changes = diff_nodes(node, &update.node, &base_path);
+        let file_name = node.file_name.clone();
+        *node = update.node;
+        if is_root {
+            // root의 file_name은 device의 절대경로이므로 유지
+            node.file_name = file_name;
+        }
+        self.update_ancestor_hashes(&update.path);
+
+        Some(changes)
+    }
+
+    // 읽지 못한 파일이면 false
+    fn fill_hash(node: &mut FileNode, path: &Path, scan_context: &ScanContext) -> bool {
+        if node.kind == FileKind::File {
+            node.hash = scan_context
+                .hash_cache
+                .digest(path, node.size, node.modified);
+            if node.hash.is_none() {
+                scan_context.mark_unreadable(path);
+                return false;
+            }
+        }
+        true
+    }
+
+    // symlink policy에 따라 node를 정리하고, tree에 포함하지 않아야 하면 false
+    fn resolve_symlink(node: &mut FileNode, path: &Path, scan_context: &ScanContext) -> bool {
+        node.link_target = fs::read_link(path)
+            .ok()
+            .map(|target| target.to_string_lossy().into_owned());
+
+        match scan_context.symlink_policy {
+            SymlinkPolicy::Skip => false,
+            SymlinkPolicy::Record => true,
+            SymlinkPolicy::FollowWithinRoot => {
+                if scan_context.is_within_root(path) == false {
+                    log::warn!(
+                        "root 밖을 가리키는 symlink는 따라가지 않습니다: {:?} -> {:?}",
+                        path,
+                        node.link_target
+                    );
+                    return true;
+                }
+
+                let target_metadata = match fs::metadata(path) {
+                    Ok(target_metadata) => target_metadata,
+                    Err(_) => return true, // 깨진 link
+                };
+
+                if target_metadata.is_dir() {
+                    if let Some(key) = dir_key(path) {
+                        if scan_context.ancestors.contains(&key) {
+                            log::warn!("순환하는 symlink는 따라가지 않습니다: {:?}", path);
+                            return true;
+                        }
                     }
                 }
+
+                node.set_metadata(&target_metadata);
+                true
+            }
+        }
+    }
+
+    // 읽지 못한 directory면 false
+    fn build_tree(
+        current_node: &mut FileNode,
+        path: &Path,
+        scan_context: &mut ScanContext,
+    ) -> bool {
+        if current_node.kind != FileKind::Directory || scan_context.is_cancelled() {
+            return true;
+        }
+        // 하위 entry를 모두 탐색한 directory만 Merkle digest를 가짐
+        current_node.hash = None;
+
+        if let Some(max_depth) = scan_context.max_depth {
+            if scan_context.depth >= max_depth {
+                // 비어있지 않은 directory만 나중에 펼칠 수 있도록 표시
+                current_node.unexpanded = match fs::read_dir(path) {
+                    Ok(mut entries) => entries.next().is_some(),
+                    Err(_) => {
+                        scan_context.mark_unreadable(path);
+                        return false;
+                    }
+                };
+                return true;
+            }
+        }
+
+        let has_key = match dir_key(path) {
+            Some(key) if scan_context.ancestors.contains(&key) => {
+                log::warn!("이미 탐색 중인 directory입니다: {:?}", path);
+                return true;
+            }
+            Some(key) => {
+                scan_context.ancestors.push(key);
+                true
+            }
+            None => false,
+        };
+
+        let dir_ignore = IgnoreRules::load_dir(path);
+        let has_dir_ignore = dir_ignore.is_some();
+        scan_context.ignore_stack.extend(dir_ignore);
+
+        let (is_readable, mut child_entries) =
+            match FileSystem::read_child_entries(path, scan_context) {
+                Some(child_entries) => (true, child_entries),
+                None => (false, Vec::new()),
+            };
+
+        scan_context.depth += 1;
+        let readable_children: Vec<bool> = if scan_context.parallel {
+            // 각 하위 directory는 복제된 context로 독립적으로 탐색 (rayon의 work stealing)
+            child_entries
+                .par_iter_mut()
+                .map(|(child_node, child_path)| {
+                    let mut child_context = scan_context.clone();
+                    FileSystem::scan_child(child_node, child_path, &mut child_context)
+                })
+                .collect()
+        } else {
+            child_entries
+                .iter_mut()
+                .map(|(child_node, child_path)| {
+                    FileSystem::scan_child(child_node, child_path, scan_context)
+                })
+                .collect()
+        };
+        scan_context.depth -= 1;
+
+        // 읽지 못한 entry는 공유하지 않음
+        for ((child_node, _), is_readable) in child_entries.into_iter().zip(readable_children) {
+            if is_readable {
+                current_node.add_child(child_node);
+            }
+        }
+        // read_dir의 순서는 운영체제와 file system마다 다르므로 정렬
+        current_node.children.sort_by(FileNode::canonical_order);
+        current_node.hash = Some(current_node.merkle_digest());
+
+        if has_dir_ignore {
+            scan_context.ignore_stack.pop();
+        }
+        if has_key {
+            scan_context.ancestors.pop();
+        }
+        is_readable
+    }
+
+    // ignore rule과 symlink policy를 적용한 하위 entry들 (아직 hash와 하위 tree는 비어있음)
+    // directory를 읽지 못하면 None
+    fn read_child_entries(
+        path: &Path,
+        scan_context: &ScanContext,
+    ) -> Option<Vec<(FileNode, PathBuf)>> {
+        let mut child_entries: Vec<(FileNode, PathBuf)> = Vec::new();
+
+        let entries = match fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(_) => {
+                scan_context.mark_unreadable(path);
+                return None;
+            }
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(_) => {
+                    scan_context.mark_unreadable(path);
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+
+            if scan_context.ignore_rules.is_ignored_with(
+                &scan_context.ignore_stack,
+                &path,
+                path.is_dir(),
+            ) {
+                continue;
+            }
+
+            let mut child_node = FileNode::new(&file_name, false).unwrap();
+            if let Ok(metadata) = fs::symlink_metadata(&path) {
+                child_node.set_metadata(&metadata);
             }
+            if child_node.kind == FileKind::Symlink
+                && FileSystem::resolve_symlink(&mut child_node, &path, scan_context) == false
+            {
+                continue;
+            }
+
+            child_entries.push((child_node, path));
         }
+
+        Some(child_entries)
+    }
+
+    // 읽지 못한 entry면 false
+    fn scan_child(
+        child_node: &mut FileNode,
+        child_path: &Path,
+        scan_context: &mut ScanContext,
+    ) -> bool {
+        let is_readable = FileSystem::fill_hash(child_node, child_path, scan_context)
+            && FileSystem::build_tree(child_node, child_path, scan_context);
+
+        if let Some(progress) = scan_context.progress {
+            progress.scanned_entries.fetch_add(1, Ordering::Relaxed);
+        }
+        is_readable
     }
 }
 
+// 다른 모듈의 테스트에서 test_dir을 스캔한 FileSystem이 필요할 때 사용
+#[cfg(test)]
+pub(crate) fn scan_test_dir(test_dir: &str) -> FileSystem {
+    let mut file_system = FileSystem::new(test_dir).unwrap();
+    file_system.init_file_node().unwrap();
+    file_system
+}
+
 #[cfg(test)]
 mod tests {
+    use super::super::hash;
     use super::*;
     use std::fs::{self, File};
     use std::io::Write;
@@ -188,19 +977,501 @@ mod tests {
     fn test_file_system_new_with_existing_root() {
         let test_dir = setup_test_directory();
 
-        let fs = FileSystem::new(&mut test_dir.to_string());
+        let fs = FileSystem::new(test_dir).unwrap();
         println!("{}", fs.node.file_name);
         assert_eq!(fs.node.file_name, "test_root");
     }
 
+    #[test]
+    fn test_file_system_new_missing_root() {
+        // 다른 경로로 대체하지 않음
+        assert!(matches!(
+            FileSystem::new("test_root_missing"),
+            Err(FsError::RootNotFound(_))
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[ignore = "root 권한으로 실행하면 권한이 없는 directory도 읽을 수 있음, root가 아닌 사용자로 --ignored와 함께 실행"]
+    fn test_file_system_partial_scan() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let test_dir = "test_root_partial_scan";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/locked", test_dir)).unwrap();
+        fs::write(format!("{}/locked/a.txt", test_dir), b"a").unwrap();
+        fs::write(format!("{}/b.txt", test_dir), b"b").unwrap();
+
+        let locked_path = Path::new(test_dir).join("locked");
+        fs::set_permissions(&locked_path, fs::Permissions::from_mode(0o000)).unwrap();
+        let is_readable = fs::read_dir(&locked_path).is_ok();
+
+        let mut file_system = FileSystem::new(test_dir).unwrap();
+        let scan_result = file_system.init_file_node();
+        fs::set_permissions(&locked_path, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(
+            is_readable == false,
+            "권한이 없는 directory를 읽을 수 있습니다. root가 아닌 사용자로 실행해주세요."
+        );
+
+        match scan_result {
+            Err(FsError::PartialScan(paths)) => assert_eq!(paths, vec![locked_path]),
+            other => panic!("PartialScan이 아닙니다: {:?}", other),
+        }
+        // 읽지 못한 경로는 tree에서 제외되고 나머지는 포함
+        assert!(file_system.lookup("b.txt").is_some());
+        assert!(file_system.lookup("locked").is_none());
+    }
+
+    // socket은 root 권한으로도 열 수 없으므로 실행하는 사용자와 관계없이 읽지 못하는 파일
+    #[cfg(unix)]
+    #[test]
+    fn test_file_system_partial_scan_unreadable_file() {
+        use std::os::unix::net::UnixListener;
+
+        let test_dir = "test_root_partial_scan_socket";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/sub/a.txt", test_dir), b"a").unwrap();
+        let socket_path = Path::new(test_dir).join("sub").join("daemon.sock");
+        let _listener = UnixListener::bind(&socket_path).unwrap();
+
+        let mut file_system = FileSystem::new(test_dir).unwrap();
+        match file_system.init_file_node() {
+            Err(FsError::PartialScan(paths)) => assert_eq!(paths, vec![socket_path.clone()]),
+            other => panic!("PartialScan이 아닙니다: {:?}", other),
+        }
+        assert!(file_system.lookup("sub/daemon.sock").is_none());
+        assert!(file_system.lookup("sub/a.txt").is_some());
+        assert!(file_system.published().lookup("sub/daemon.sock").is_none());
+
+        // 병렬 탐색과 부분 재탐색도 같은 결과
+        let progress = ScanProgress::new();
+        assert!(matches!(
+            file_system.init_file_node_parallel(&progress),
+            Err(FsError::PartialScan(_))
+        ));
+        assert!(file_system.lookup("sub/daemon.sock").is_none());
+        fs::write(format!("{}/sub/b.txt", test_dir), b"b").unwrap();
+        let update = file_system.refresh_subtree(Path::new("sub")).unwrap();
+        assert!(update
+            .node
+            .children()
+            .iter()
+            .all(|child| child.file_name() != "daemon.sock"));
+
+        fs::remove_dir_all(test_dir).unwrap();
+    }
+
     #[test]
     fn test_file_system_init_file_node() {
         let test_dir = setup_test_directory();
 
-        let mut fs = FileSystem::new(&mut test_dir.to_string());
-        fs.init_file_node();
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.init_file_node().unwrap();
 
         assert!(fs.node.children.len() > 0); // Root should have children
-        assert_eq!(fs.node.children[0].file_name, "file2.txt");
+        assert_eq!(fs.node.children[0].file_name, "subdir");
+        assert_eq!(fs.node.children[1].file_name, "file1.txt");
+        assert_eq!(fs.node.children[2].file_name, "file2.txt");
+    }
+
+    #[test]
+    fn test_file_system_parallel_scan_matches_sequential() {
+        let test_dir = "test_root_parallel_scan";
+        let _ = fs::remove_dir_all(test_dir);
+        for dir_idx in 0..8 {
+            let dir_path = format!("{}/dir_{}/nested", test_dir, dir_idx);
+            fs::create_dir_all(&dir_path).unwrap();
+            for file_idx in 0..16 {
+                fs::write(
+                    format!("{}/file_{}.txt", dir_path, file_idx),
+                    format!("{}-{}", dir_idx, file_idx),
+                )
+                .unwrap();
+            }
+        }
+        fs::write(format!("{}/.xilersignore", test_dir), "dir_3/\n").unwrap();
+
+        let mut sequential = FileSystem::new(test_dir).unwrap();
+        sequential.init_file_node().unwrap();
+
+        let progress = ScanProgress::new();
+        let mut parallel = FileSystem::new(test_dir).unwrap();
+        parallel.init_file_node_parallel(&progress).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&sequential).unwrap(),
+            serde_json::to_string(&parallel).unwrap()
+        );
+        // .xilersignore 1개 + directory 7 * (1 + 1 + 16)
+        assert_eq!(progress.scanned_entries(), 1 + 7 * 18);
+    }
+
+    #[test]
+    fn test_file_system_parallel_scan_cancelled() {
+        let test_dir = "test_root_parallel_scan_cancelled";
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::write(format!("{}/sub/a.txt", test_dir), b"a").unwrap();
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        let progress = ScanProgress::new();
+        progress.cancel();
+
+        assert!(matches!(
+            fs.init_file_node_parallel(&progress),
+            Err(FsError::Cancelled)
+        ));
+        assert!(fs.node.children.is_empty());
+        assert_eq!(progress.scanned_entries(), 0);
+    }
+
+    #[test]
+    fn test_file_system_merkle_hash() {
+        let test_dir = "test_root_merkle_hash";
+        let _ = fs::remove_dir_all(test_dir);
+        for replica in ["left", "right"] {
+            fs::create_dir_all(format!("{}/{}/docs/deep", test_dir, replica)).unwrap();
+            fs::create_dir_all(format!("{}/{}/src", test_dir, replica)).unwrap();
+            fs::write(format!("{}/{}/docs/deep/a.txt", test_dir, replica), b"a").unwrap();
+            fs::write(format!("{}/{}/src/main.rs", test_dir, replica), b"fn").unwrap();
+        }
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.init_file_node().unwrap();
+
+        // 이름이 다른 directory여도 내용이 같으면 같은 digest
+        let left = fs.lookup("left").unwrap();
+        let right = fs.lookup("right").unwrap();
+        assert!(left.hash().is_some());
+        assert_eq!(left.hash(), right.hash());
+        let root_hash = fs.node.hash().unwrap().to_string();
+        let src_hash = fs.lookup("left/src").unwrap().hash().unwrap().to_string();
+
+        // 깊은 곳의 파일이 바뀌면 root까지의 digest만 바뀜
+        fs::write(format!("{}/left/docs/deep/a.txt", test_dir), b"changed").unwrap();
+        let update = fs.refresh_subtree(Path::new("left/docs/deep")).unwrap();
+        assert_eq!(update.path, vec!["left", "docs", "deep"]);
+
+        let left = fs.lookup("left").unwrap();
+        let right = fs.lookup("right").unwrap();
+        assert!(left.hash() != right.hash());
+        assert!(fs.node.hash() != Some(root_hash.as_str()));
+        assert_eq!(
+            fs.lookup("left/src").unwrap().hash(),
+            Some(src_hash.as_str())
+        );
+
+        // 부분 갱신 결과는 전체를 다시 탐색한 결과와 같음
+        let mut rescanned = FileSystem::new(test_dir).unwrap();
+        rescanned.init_file_node().unwrap();
+        assert_eq!(fs.node.hash(), rescanned.node.hash());
+    }
+
+    #[test]
+    fn test_file_system_children_canonical_order() {
+        let test_dir = "test_root_canonical_order";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/zeta", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/Alpha", test_dir)).unwrap();
+        for name in ["b.txt", "A2.txt", "a1.txt", "Ärger.txt", "c.txt"] {
+            File::create(format!("{}/{}", test_dir, name)).unwrap();
+        }
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.init_file_node().unwrap();
+
+        let names: Vec<&str> = fs
+            .node
+            .children
+            .iter()
+            .map(|child| child.file_name())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Alpha",
+                "zeta",
+                "a1.txt",
+                "A2.txt",
+                "Ärger.txt",
+                "b.txt",
+                "c.txt"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_system_init_file_node_metadata() {
+        let test_dir = "test_root_metadata";
+        fs::create_dir_all(format!("{}/empty_dir", test_dir)).unwrap();
+        let mut file = File::create(format!("{}/data.bin", test_dir)).unwrap();
+        file.write_all(&[0u8; 1500]).unwrap();
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.init_file_node().unwrap();
+
+        assert_eq!(fs.node.kind(), FileKind::Directory);
+
+        let empty_dir = fs
+            .node
+            .children()
+            .iter()
+            .find(|node| node.file_name() == "empty_dir")
+            .unwrap();
+        assert_eq!(empty_dir.kind(), FileKind::Directory);
+        assert!(empty_dir.children().is_empty());
+
+        let data = fs
+            .node
+            .children()
+            .iter()
+            .find(|node| node.file_name() == "data.bin")
+            .unwrap();
+        assert_eq!(data.kind(), FileKind::File);
+        assert_eq!(data.size(), 1500);
+        assert!(data.modified().is_some());
+    }
+
+    #[test]
+    fn test_file_system_init_file_node_hash() {
+        let test_dir = "test_root_content_hash";
+        fs::create_dir_all(format!("{}/nested", test_dir)).unwrap();
+        fs::write(format!("{}/a.txt", test_dir), b"same").unwrap();
+        fs::write(format!("{}/nested/b.txt", test_dir), b"same").unwrap();
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.init_file_node().unwrap();
+
+        let a = fs.lookup("a.txt").unwrap();
+        let b = fs.lookup("nested/b.txt").unwrap();
+        assert_eq!(a.hash(), Some(hash::hash_bytes(b"same").as_str()));
+        assert_eq!(a.hash(), b.hash());
+        assert!(fs.lookup("nested").unwrap().hash().is_some());
+
+        // 재탐색해도 tree가 중복되지 않고 cache가 재사용됨
+        fs.init_file_node().unwrap();
+        assert_eq!(fs.node.children().len(), 2);
+        assert_eq!(fs.hash_cache.len(), 2);
+    }
+
+    #[test]
+    fn test_file_system_refresh_and_apply_subtree() {
+        let test_dir = "test_root_subtree";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/docs", test_dir)).unwrap();
+        fs::write(format!("{}/docs/a.txt", test_dir), b"a").unwrap();
+
+        let mut local_fs = FileSystem::new(test_dir).unwrap();
+        local_fs.init_file_node().unwrap();
+        let mut master_fs = local_fs.clone();
+
+        fs::write(format!("{}/docs/b.txt", test_dir), b"b").unwrap();
+        let update = local_fs.refresh_subtree(Path::new("docs")).unwrap();
+        assert_eq!(update.path, vec!["docs".to_string()]);
+        assert_eq!(update.node.children().len(), 2);
+
+        assert!(master_fs.lookup("docs/b.txt").is_none());
+        assert_eq!(master_fs.apply_subtree_update(update).unwrap().len(), 1);
+        assert!(master_fs.lookup("docs/b.txt").is_some());
+
+        // 변경이 없으면 전달할 변경분도 없음
+        assert!(local_fs.refresh_subtree(Path::new("docs")).is_none());
+
+        // 삭제된 directory는 상위 directory를 다시 탐색
+        fs::remove_dir_all(format!("{}/docs", test_dir)).unwrap();
+        let update = local_fs.refresh_subtree(Path::new("docs")).unwrap();
+        assert!(update.path.is_empty());
+        assert!(master_fs.apply_subtree_update(update).is_some());
+        assert!(master_fs.lookup("docs").is_none());
+        assert_eq!(master_fs.node.file_name(), test_dir);
+    }
+
+    #[test]
+    fn test_file_system_ignore_rules() {
+        let test_dir = "test_root_ignore";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/.git/objects", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/src", test_dir)).unwrap();
+        fs::write(format!("{}/src/.xilersignore", test_dir), "*.swp\n").unwrap();
+        fs::write(format!("{}/src/main.rs", test_dir), b"").unwrap();
+        fs::write(format!("{}/src/.main.rs.swp", test_dir), b"").unwrap();
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.set_ignore_patterns(&[".git/".to_string()]);
+        fs.init_file_node().unwrap();
+
+        assert!(fs.lookup(".git").is_none());
+        assert!(fs.lookup("src/main.rs").is_some());
+        assert!(fs.lookup("src/.main.rs.swp").is_none());
+        assert!(fs.is_ignored(&Path::new(test_dir).join("src/.main.rs.swp")));
+
+        // subtree를 다시 탐색할 때도 상위 directory의 rule이 적용됨
+        fs::write(format!("{}/src/lib.rs.swp", test_dir), b"").unwrap();
+        fs::write(format!("{}/src/lib.rs", test_dir), b"").unwrap();
+        let update = fs.refresh_subtree(Path::new("src")).unwrap();
+        assert_eq!(update.node.children().len(), 3);
+        assert!(fs.lookup("src/lib.rs.swp").is_none());
+    }
+
+    #[test]
+    fn test_file_system_scan_depth_and_expand() {
+        let test_dir = "test_root_scan_depth";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/a/b/c", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/empty", test_dir)).unwrap();
+        fs::write(format!("{}/a/b/c/deep.txt", test_dir), b"deep").unwrap();
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.set_scan_depth(Some(2));
+        fs.init_file_node().unwrap();
+
+        assert!(fs.lookup("empty").unwrap().is_expanded());
+        let b = fs.lookup("a/b").unwrap();
+        assert!(b.is_expanded() == false);
+        assert!(b.children().is_empty());
+
+        // 탐색하지 않은 영역의 변경은 전달하지 않음
+        fs::write(format!("{}/a/b/c/new.txt", test_dir), b"new").unwrap();
+        assert!(fs.refresh_subtree(Path::new("a/b/c")).is_none());
+
+        let update = fs.expand_subtree(Path::new("a/b")).unwrap();
+        assert_eq!(update.path, vec!["a".to_string(), "b".to_string()]);
+        assert!(update.node.is_expanded());
+        // 펼친 directory 기준으로 다시 scan_depth만큼 탐색
+        assert!(fs.lookup("a/b/c/deep.txt").is_some());
+
+        let mut master_fs = FileSystem::new(test_dir).unwrap();
+        master_fs.set_scan_depth(Some(2));
+        master_fs.init_file_node().unwrap();
+        master_fs.apply_subtree_update(update).unwrap();
+        assert!(master_fs.lookup("a/b/c/new.txt").is_some());
+    }
+
+    #[cfg(unix)]
+    fn setup_symlink_directory(test_dir: &str, outside_dir: &str) {
+        use std::os::unix::fs::symlink;
+
+        let _ = fs::remove_dir_all(test_dir);
+        let _ = fs::remove_dir_all(outside_dir);
+        fs::create_dir_all(format!("{}/sub", test_dir)).unwrap();
+        fs::create_dir_all(outside_dir).unwrap();
+        fs::write(format!("{}/sub/a.txt", test_dir), b"a").unwrap();
+        fs::write(format!("{}/secret.txt", outside_dir), b"secret").unwrap();
+
+        symlink("..", format!("{}/sub/loop", test_dir)).unwrap();
+        symlink("sub/a.txt", format!("{}/link_a.txt", test_dir)).unwrap();
+        symlink("sub", format!("{}/link_sub", test_dir)).unwrap();
+        symlink(
+            fs::canonicalize(outside_dir).unwrap(),
+            format!("{}/escape", test_dir),
+        )
+        .unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policy_record() {
+        let test_dir = "test_root_symlink_record";
+        setup_symlink_directory(test_dir, "test_outside_symlink_record");
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.init_file_node().unwrap();
+
+        let loop_node = fs.lookup("sub/loop").unwrap();
+        assert_eq!(loop_node.kind(), FileKind::Symlink);
+        assert_eq!(loop_node.link_target(), Some(".."));
+        assert!(loop_node.children().is_empty());
+
+        let escape_node = fs.lookup("escape").unwrap();
+        assert_eq!(escape_node.kind(), FileKind::Symlink);
+        assert!(escape_node.children().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_rescan_outside_root() {
+        let test_dir = "test_root_symlink_rescan";
+        let outside_dir = "test_outside_symlink_rescan";
+
+        for symlink_policy in [SymlinkPolicy::Record, SymlinkPolicy::FollowWithinRoot] {
+            setup_symlink_directory(test_dir, outside_dir);
+            let mut fs = FileSystem::new(test_dir).unwrap();
+            fs.set_symlink_policy(symlink_policy);
+            fs.init_file_node().unwrap();
+
+            // 다른 device의 탐색 요청이나 watcher의 변경 감지로도 root 밖을 탐색하지 않음
+            assert!(fs.expand_subtree(Path::new("escape")).is_none());
+            let _ = fs.refresh_subtree(Path::new("escape"));
+            assert_eq!(fs.lookup("escape").unwrap().kind(), FileKind::Symlink);
+            assert!(fs.lookup("escape/secret.txt").is_none());
+            assert!(fs.published().lookup("escape/secret.txt").is_none());
+
+            // 순환하는 link도 따라가지 않음
+            assert!(fs.expand_subtree(Path::new("sub/loop")).is_none());
+            assert_eq!(fs.lookup("sub/loop").unwrap().kind(), FileKind::Symlink);
+        }
+
+        // 따라간 root 안의 link는 다시 탐색할 수 있음
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.set_symlink_policy(SymlinkPolicy::FollowWithinRoot);
+        fs.init_file_node().unwrap();
+        assert!(fs.expand_subtree(Path::new("link_sub")).is_some());
+        assert!(fs.lookup("link_sub/a.txt").is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policy_skip() {
+        let test_dir = "test_root_symlink_skip";
+        setup_symlink_directory(test_dir, "test_outside_symlink_skip");
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.set_symlink_policy(SymlinkPolicy::Skip);
+        fs.init_file_node().unwrap();
+
+        assert!(fs.lookup("sub/loop").is_none());
+        assert!(fs.lookup("escape").is_none());
+        assert!(fs.lookup("sub/a.txt").is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policy_follow_within_root() {
+        let test_dir = "test_root_symlink_follow";
+        setup_symlink_directory(test_dir, "test_outside_symlink_follow");
+
+        let mut fs = FileSystem::new(test_dir).unwrap();
+        fs.set_symlink_policy(SymlinkPolicy::FollowWithinRoot);
+        fs.init_file_node().unwrap();
+
+        // root를 가리키는 순환 link는 따라가지 않음
+        let loop_node = fs.lookup("sub/loop").unwrap();
+        assert_eq!(loop_node.kind(), FileKind::Symlink);
+        assert!(loop_node.children().is_empty());
+
+        // root 밖을 가리키는 link는 따라가지 않음
+        let escape_node = fs.lookup("escape").unwrap();
+        assert_eq!(escape_node.kind(), FileKind::Symlink);
+        assert!(fs.lookup("escape/secret.txt").is_none());
+
+        // root 안을 가리키는 link는 대상의 내용으로 기록
+        let link_a = fs.lookup("link_a.txt").unwrap();
+        assert_eq!(link_a.kind(), FileKind::File);
+        assert_eq!(link_a.link_target(), Some("sub/a.txt"));
+        assert_eq!(link_a.hash(), Some(hash::hash_bytes(b"a").as_str()));
+        assert!(fs.lookup("link_sub/a.txt").is_some());
+        assert_eq!(
+            fs.lookup("link_sub/loop").unwrap().kind(),
+            FileKind::Symlink
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
     }
 }
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// 파일 내용의 BLAKE3 digest (hex 문자열)
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    blake3::hash(bytes).to_hex().to_string()
+}
+
+#[derive(Clone)]
+struct CacheEntry {
+    size: u64,
+    modified: Option<u64>,
+    digest: String,
+}
+
+// (path, size, mtime)이 동일하면 재계산하지 않고 이전 digest를 사용
+// 병렬 탐색 중에 공유되므로 hash 계산은 lock 밖에서 진행
+#[derive(Default)]
+pub struct HashCache {
+    entries: Mutex<HashMap<PathBuf, CacheEntry>>,
+}
+
+impl Clone for HashCache {
+    fn clone(&self) -> Self {
+        HashCache {
+            entries: Mutex::new(self.entries.lock().unwrap().clone()),
+        }
+    }
+}
+
+impl HashCache {
+    pub fn new() -> Self {
+        HashCache {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn digest(&self, path: &Path, size: u64, modified: Option<u64>) -> Option<String> {
+        if let Some(entry) = self.entries.lock().unwrap().get(path) {
+            if entry.size == size && entry.modified == modified {
+                return Some(entry.digest.clone());
+            }
+        }
+
+        match hash_file(path) {
+            Ok(digest) => {
+                self.entries.lock().unwrap().insert(
+                    path.to_path_buf(),
+                    CacheEntry {
+                        size,
+                        modified,
+                        digest: digest.clone(),
+                    },
+                );
+                Some(digest)
+            }
+            Err(e) => {
+                log::warn!("파일({:?})의 hash를 계산하지 못했습니다: {}", path, e);
+                self.entries.lock().unwrap().remove(path);
+                None
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().unwrap().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    #[test]
+    fn test_hash_file_matches_hash_bytes() {
+        let test_dir = "test_root_hash";
+        fs::create_dir_all(test_dir).unwrap();
+        let path = Path::new(test_dir).join("content.txt");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(b"xilers").unwrap();
+
+        assert_eq!(hash_file(&path).unwrap(), hash_bytes(b"xilers"));
+    }
+
+    #[test]
+    fn test_hash_cache_reuses_unchanged_entry() {
+        let test_dir = "test_root_hash_cache";
+        fs::create_dir_all(test_dir).unwrap();
+        let path = Path::new(test_dir).join("content.txt");
+        fs::write(&path, b"before").unwrap();
+
+        let cache = HashCache::new();
+        let first = cache.digest(&path, 6, Some(1)).unwrap();
+
+        // size와 mtime이 같으면 내용이 바뀌어도 cache된 값을 사용
+        fs::write(&path, b"after!").unwrap();
+        assert_eq!(cache.digest(&path, 6, Some(1)).unwrap(), first);
+
+        // mtime이 바뀌면 다시 계산
+        assert_eq!(
+            cache.digest(&path, 6, Some(2)).unwrap(),
+            hash_bytes(b"after!")
+        );
+        assert_eq!(cache.len(), 1);
+    }
+}
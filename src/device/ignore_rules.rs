@@ -0,0 +1,146 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+// shared root 아래 어느 directory에나 둘 수 있는 gitignore 형식의 파일
+pub const IGNORE_FILE_NAME: &str = ".xilersignore";
+
+// client config의 전역 ignore 목록과 각 directory의 .xilersignore를 함께 적용
+#[derive(Clone)]
+pub struct IgnoreRules {
+    root: PathBuf,
+    global: Gitignore,
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        IgnoreRules {
+            root: PathBuf::new(),
+            global: Gitignore::empty(),
+        }
+    }
+}
+
+impl IgnoreRules {
+    pub fn new(root: &Path, global_patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new(root);
+        for pattern in global_patterns {
+            if let Err(e) = builder.add_line(None, pattern) {
+                log::warn!("잘못된 ignore pattern입니다({:?}): {}", pattern, e);
+            }
+        }
+
+        let global = builder.build().unwrap_or_else(|e| {
+            log::warn!("전역 ignore 목록을 구성하지 못했습니다: {}", e);
+            Gitignore::empty()
+        });
+
+        IgnoreRules {
+            root: root.to_path_buf(),
+            global,
+        }
+    }
+
+    // dir에 .xilersignore가 있으면 해당 directory 기준의 rule을 반환
+    pub fn load_dir(dir: &Path) -> Option<Gitignore> {
+        let ignore_file = dir.join(IGNORE_FILE_NAME);
+        if ignore_file.is_file() == false {
+            return None;
+        }
+
+        let (gitignore, error) = Gitignore::new(&ignore_file);
+        if let Some(e) = error {
+            log::warn!("{:?}를 해석하는 중 문제가 발생했습니다: {}", ignore_file, e);
+        }
+
+        Some(gitignore)
+    }
+
+    // root부터 dir까지 각 directory의 rule (build_tree 도중에 subtree를 다시 탐색할 때 사용)
+    pub fn load_ancestors(&self, dir: &Path) -> Vec<Gitignore> {
+        let relative_dir = match dir.strip_prefix(&self.root) {
+            Ok(relative_dir) => relative_dir,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut current_dir = self.root.clone();
+        let mut stack: Vec<Gitignore> = IgnoreRules::load_dir(&current_dir).into_iter().collect();
+        for component in relative_dir.components() {
+            current_dir.push(component);
+            stack.extend(IgnoreRules::load_dir(&current_dir));
+        }
+
+        stack
+    }
+
+    // stack은 root부터 path의 상위 directory까지의 rule이며, 가장 가까운 rule이 우선
+    pub fn is_ignored_with(&self, stack: &[Gitignore], path: &Path, is_dir: bool) -> bool {
+        for gitignore in stack.iter().rev() {
+            let matched = gitignore.matched(path, is_dir);
+            if matched.is_none() == false {
+                return matched.is_ignore();
+            }
+        }
+
+        self.global.matched(path, is_dir).is_ignore()
+    }
+
+    // 상위 directory 중 하나라도 무시되면 path도 무시됨
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let relative_path = match path.strip_prefix(&self.root) {
+            Ok(relative_path) => relative_path,
+            Err(_) => return false,
+        };
+
+        let mut current_path = self.root.clone();
+        let mut stack: Vec<Gitignore> = IgnoreRules::load_dir(&current_path).into_iter().collect();
+        for component in relative_path.components() {
+            current_path.push(component);
+            let is_dir = current_path.is_dir();
+
+            if self.is_ignored_with(&stack, &current_path, is_dir) {
+                return true;
+            }
+            if is_dir {
+                stack.extend(IgnoreRules::load_dir(&current_path));
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_ignore_rules_nested_and_global() {
+        let test_dir = "test_root_ignore_rules";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/sub/keep", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/node_modules/pkg", test_dir)).unwrap();
+        fs::write(format!("{}/{}", test_dir, IGNORE_FILE_NAME), "*.log\n").unwrap();
+        fs::write(
+            format!("{}/sub/{}", test_dir, IGNORE_FILE_NAME),
+            "!important.log\nkeep/\n",
+        )
+        .unwrap();
+        fs::write(format!("{}/a.log", test_dir), b"").unwrap();
+        fs::write(format!("{}/sub/b.log", test_dir), b"").unwrap();
+        fs::write(format!("{}/sub/important.log", test_dir), b"").unwrap();
+        fs::write(format!("{}/sub/keep/c.txt", test_dir), b"").unwrap();
+        fs::write(format!("{}/node_modules/pkg/index.js", test_dir), b"").unwrap();
+
+        let root = Path::new(test_dir);
+        let rules = IgnoreRules::new(root, &["node_modules".to_string()]);
+
+        assert!(rules.is_ignored(&root.join("a.log")));
+        assert!(rules.is_ignored(&root.join("sub/b.log")));
+        assert!(rules.is_ignored(&root.join("sub/important.log")) == false);
+        assert!(rules.is_ignored(&root.join("sub/keep/c.txt")));
+        assert!(rules.is_ignored(&root.join("node_modules/pkg/index.js")));
+        assert!(rules.is_ignored(&root.join(IGNORE_FILE_NAME)) == false);
+        assert!(rules.is_ignored(Path::new("/etc/passwd")) == false);
+    }
+}
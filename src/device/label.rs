@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::namespace;
+use super::rel_path::{case_fold_key, normalize_name};
+use super::spec::DeviceSpec;
+
+pub const MAX_LABEL_LEN: usize = 32;
+
+// 사용자가 정한 device 이름과 tag
+// label은 namespace의 경로와 CLI에서 UUID 대신 사용하므로 group 안에서 대소문자와 관계없이 유일해야 함
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceLabel {
+    #[serde(default)]
+    pub label: String, // 빈 문자열이면 label 없음
+    #[serde(default)]
+    pub tags: Vec<String>, // e.g. nas, work
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LabelError {
+    TooLong(String),
+    InvalidChar(String, char),
+    Reserved(String),  // 경로나 목록 번호로 해석되는 이름
+    Duplicate(String), // group의 다른 device가 사용 중
+}
+
+impl std::fmt::Display for LabelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LabelError::TooLong(name) => {
+                write!(f, "{}자 이하로 입력해주세요: {}", MAX_LABEL_LEN, name)
+            }
+            LabelError::InvalidChar(name, c) => {
+                write!(
+                    f,
+                    "사용할 수 없는 문자({:?})가 포함되어 있습니다: {}",
+                    c, name
+                )
+            }
+            LabelError::Reserved(name) => {
+                write!(
+                    f,
+                    "'.', '..', 숫자로만 된 이름은 label로 사용할 수 없습니다: {}",
+                    name
+                )
+            }
+            LabelError::Duplicate(label) => {
+                write!(f, "group의 다른 device가 사용 중인 label입니다: {}", label)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LabelError {}
+
+impl DeviceLabel {
+    // 앞뒤 공백을 제거하고 NFC로 정규화, 중간의 공백은 '-'로 치환
+    // tag는 소문자로 바꾸고 중복을 제거해서 정렬
+    pub fn normalized(&self) -> Result<DeviceLabel, LabelError> {
+        let label = normalize_label(&self.label)?;
+        // label은 namespace의 directory 이름이고, CLI에서는 숫자를 목록의 번호로 해석
+        if label == "."
+            || label == ".."
+            || (label.is_empty() == false && label.chars().all(|c| c.is_ascii_digit()))
+        {
+            return Err(LabelError::Reserved(label));
+        }
+
+        let mut tags = Vec::new();
+        for tag in &self.tags {
+            let tag = normalize_label(tag)?.to_lowercase();
+            if tag.is_empty() == false {
+                tags.push(tag);
+            }
+        }
+        tags.sort();
+        tags.dedup();
+
+        Ok(DeviceLabel { label, tags })
+    }
+}
+
+// '/'와 '\'는 경로 구분자, '~'는 union namespace에서 충돌 사본의 구분자, '#'은 tag 검색에 사용
+fn normalize_label(name: &str) -> Result<String, LabelError> {
+    let name: String = normalize_name(name.trim())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if let Some(c) = name
+        .chars()
+        .find(|c| matches!(c, '/' | '\\' | '~' | '#') || c.is_control())
+    {
+        return Err(LabelError::InvalidChar(name, c));
+    }
+    if name.chars().count() > MAX_LABEL_LEN {
+        return Err(LabelError::TooLong(name));
+    }
+
+    Ok(name)
+}
+
+impl DeviceSpec {
+    pub fn device_label(&self) -> DeviceLabel {
+        DeviceLabel {
+            label: self.label.clone(),
+            tags: self.tags.clone(),
+        }
+    }
+
+    pub fn set_device_label(&mut self, device_label: DeviceLabel) {
+        self.label = device_label.label;
+        self.tags = device_label.tags;
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+// id의 device가 label을 사용할 수 있는지 확인
+// label이 없는 device는 namespace에서 사용하는 이름(e.g. linux-1a2b3c4d)과 비교
+pub fn check_unique(
+    id_spec_map: &BTreeMap<Uuid, DeviceSpec>,
+    id: &Uuid,
+    label: &str,
+) -> Result<(), LabelError> {
+    if label.is_empty() {
+        return Ok(());
+    }
+
+    let key = case_fold_key(label);
+    let duplicated = id_spec_map.iter().any(|(other_id, spec)| {
+        other_id != id && case_fold_key(&namespace::device_label(other_id, Some(spec))) == key
+    });
+    match duplicated {
+        true => Err(LabelError::Duplicate(label.to_string())),
+        false => Ok(()),
+    }
+}
+
+// CLI에서 device를 지정하는 문자열을 id로 변환
+// label, UUID, UUID의 앞 8자리, 혹은 "#tag" (해당하는 device가 하나일 때만)
+pub fn find_device(id_spec_map: &BTreeMap<Uuid, DeviceSpec>, query: &str) -> Option<Uuid> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+
+    if let Ok(id) = Uuid::parse_str(query) {
+        return id_spec_map.contains_key(&id).then_some(id);
+    }
+
+    let matched: Vec<&Uuid> = match query.strip_prefix('#') {
+        Some(tag) => id_spec_map
+            .iter()
+            .filter(|(_, spec)| spec.has_tag(tag))
+            .map(|(id, _)| id)
+            .collect(),
+        None => {
+            let key = case_fold_key(query);
+            let by_label: Vec<&Uuid> = id_spec_map
+                .iter()
+                .filter(|(_, spec)| spec.label.is_empty() == false)
+                .filter(|(_, spec)| case_fold_key(&spec.label) == key)
+                .map(|(id, _)| id)
+                .collect();
+            match by_label.is_empty() {
+                true => id_spec_map
+                    .keys()
+                    .filter(|id| id.simple().to_string().starts_with(&query.to_lowercase()))
+                    .collect(),
+                false => by_label,
+            }
+        }
+    };
+
+    match matched.as_slice() {
+        [id] => Some(**id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labeled(label: &str, tags: &[&str]) -> DeviceSpec {
+        DeviceSpec {
+            label: label.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..DeviceSpec::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_label() {
+        let device_label = DeviceLabel {
+            label: "  living room NAS ".to_string(),
+            tags: vec!["Work".to_string(), "nas".to_string(), "work".to_string()],
+        };
+        assert_eq!(
+            device_label.normalized(),
+            Ok(DeviceLabel {
+                label: "living-room-NAS".to_string(),
+                tags: vec!["nas".to_string(), "work".to_string()],
+            })
+        );
+
+        let invalid = DeviceLabel {
+            label: "home/nas".to_string(),
+            tags: Vec::new(),
+        };
+        assert_eq!(
+            invalid.normalized(),
+            Err(LabelError::InvalidChar("home/nas".to_string(), '/'))
+        );
+
+        let too_long = DeviceLabel {
+            label: "a".repeat(MAX_LABEL_LEN + 1),
+            tags: Vec::new(),
+        };
+        assert!(too_long.normalized().is_err());
+
+        for reserved in [".", "..", "2", " 42 "] {
+            let device_label = DeviceLabel {
+                label: reserved.to_string(),
+                tags: Vec::new(),
+            };
+            assert_eq!(
+                device_label.normalized(),
+                Err(LabelError::Reserved(reserved.trim().to_string()))
+            );
+        }
+        let device_label = DeviceLabel {
+            label: "nas.2".to_string(),
+            tags: vec!["2".to_string()],
+        };
+        assert!(device_label.normalized().is_ok());
+    }
+
+    #[test]
+    fn test_find_device() {
+        let nas_id = Uuid::from_u128(0x1111_1111 << 96);
+        let laptop_id = Uuid::from_u128(0x2222_2222 << 96);
+        let desktop_id = Uuid::from_u128(0x3333_3333 << 96);
+        let id_spec_map = BTreeMap::from([
+            (nas_id, labeled("nas", &["home", "storage"])),
+            (laptop_id, labeled("work-laptop", &["work"])),
+            (desktop_id, labeled("", &["work", "home"])),
+        ]);
+
+        assert_eq!(find_device(&id_spec_map, "NAS"), Some(nas_id));
+        assert_eq!(find_device(&id_spec_map, "#storage"), Some(nas_id));
+        assert_eq!(find_device(&id_spec_map, "#work"), None); // 두 device가 해당
+        assert_eq!(find_device(&id_spec_map, "33333333"), Some(desktop_id));
+        assert_eq!(
+            find_device(&id_spec_map, &laptop_id.to_string()),
+            Some(laptop_id)
+        );
+        assert_eq!(find_device(&id_spec_map, "desktop"), None);
+
+        assert!(check_unique(&id_spec_map, &nas_id, "nas").is_ok());
+        assert_eq!(
+            check_unique(&id_spec_map, &desktop_id, "Work-Laptop"),
+            Err(LabelError::Duplicate("Work-Laptop".to_string()))
+        );
+        assert!(check_unique(&id_spec_map, &desktop_id, "").is_ok());
+
+        // label이 없는 device의 namespace 이름과도 겹치지 않아야 함
+        let linux_id = Uuid::from_u128(0x4444_4444 << 96);
+        let mut id_spec_map = id_spec_map;
+        id_spec_map.insert(
+            linux_id,
+            DeviceSpec {
+                os: "Linux".to_string(),
+                ..DeviceSpec::default()
+            },
+        );
+        assert_eq!(
+            check_unique(&id_spec_map, &nas_id, "LINUX-44444444"),
+            Err(LabelError::Duplicate("LINUX-44444444".to_string()))
+        );
+        assert!(check_unique(&id_spec_map, &linux_id, "linux-44444444").is_ok());
+    }
+}
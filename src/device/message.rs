@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use super::telemetry::Telemetry;
+
+// master와 client가 websocket으로 주고받는 text message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsCommand {
+    Refresh,                         // group의 device manager를 다시 받아와야 함
+    ExpandSubtree { path: String },  // 해당 경로(root 기준, '/' 구분)를 탐색해서 master에 전달
+    Telemetry { sample: Telemetry }, // client -> master, group에 전달하지 않음
+}
+
+impl WsCommand {
+    pub fn to_text(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    // 해석할 수 없는 message는 이전 버전과 같이 갱신 요청으로 취급
+    pub fn from_text(text: &str) -> Self {
+        serde_json::from_str(text).unwrap_or(WsCommand::Refresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ws_command_round_trip() {
+        let command = WsCommand::ExpandSubtree {
+            path: "a/b".to_string(),
+        };
+
+        assert_eq!(WsCommand::from_text(&command.to_text()), command);
+        assert_eq!(WsCommand::from_text(""), WsCommand::Refresh);
+
+        let command = WsCommand::Telemetry {
+            sample: Telemetry {
+                timestamp: 1,
+                load_average: Some(0.5),
+                ..Telemetry::default()
+            },
+        };
+        assert_eq!(WsCommand::from_text(&command.to_text()), command);
+    }
+}
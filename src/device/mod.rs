@@ -1,2 +1,19 @@
+pub mod codec;
+pub mod diff;
+pub mod endpoint;
 pub mod file_sys;
+pub mod hash;
+pub mod ignore_rules;
+pub mod label;
+pub mod message;
+pub mod namespace;
+pub mod protocol;
+pub mod query;
+pub mod rel_path;
+pub mod render;
+pub mod share_policy;
 pub mod spec;
+pub mod stats;
+pub mod telemetry;
+pub mod transfer;
+pub mod watcher;
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::file_sys::{FileKind, FileNode, FileSystem};
+use super::spec::DeviceSpec;
+
+// group에 속한 모든 device의 tree를 하나의 가상 경로 공간으로 묶는 방식
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamespaceView {
+    #[default]
+    ByDevice, // /<device-label>/...
+    Union, // 같은 경로를 하나로 겹쳐서 보여줌
+}
+
+// 사용자가 정한 label이 있으면 그대로 사용하고, 없으면 os와 UUID의 앞 8자리로 만듦
+// 경로에 사용할 수 있도록 '/'와 공백을 '-'로 치환
+pub fn device_label(id: &Uuid, spec: Option<&DeviceSpec>) -> String {
+    let short_id = &id.simple().to_string()[..8];
+    match spec {
+        Some(spec) if spec.label.is_empty() == false => spec.label.clone(),
+        Some(spec) if spec.os.is_empty() == false => {
+            let os = spec
+                .os
+                .to_lowercase()
+                .replace(|c: char| c == '/' || c.is_whitespace(), "-");
+            format!("{}-{}", os, short_id)
+        }
+        _ => short_id.to_string(),
+    }
+}
+
+// root의 이름은 빈 문자열이며 출력하면 "/"
+pub fn build_namespace(
+    id_spec_map: &BTreeMap<Uuid, DeviceSpec>,
+    id_fs_map: &BTreeMap<Uuid, FileSystem>,
+    view: NamespaceView,
+) -> FileNode {
+    let mut root = FileNode::virtual_dir("");
+
+    for (id, fs) in id_fs_map {
+        let label = device_label(id, id_spec_map.get(id));
+        match view {
+            NamespaceView::ByDevice => {
+                let mut device_root = fs.node.clone();
+                device_root.rename(label);
+                root.children_mut().push(device_root);
+            }
+            NamespaceView::Union => overlay(&mut root, &fs.node, &label),
+        }
+    }
+
+    root.children_mut().sort_by(FileNode::canonical_order);
+    root.update_merkle_digest();
+    root
+}
+
+// other의 하위 entry들을 base에 겹침
+// 같은 경로의 directory는 합치고, 내용이 다른 파일은 "이름~device-label"로 함께 보여줌
+fn overlay(base: &mut FileNode, other: &FileNode, label: &str) {
+    for other_child in other.children() {
+        let existing = base
+            .children_mut()
+            .iter_mut()
+            .find(|child| child.file_name() == other_child.file_name());
+
+        match existing {
+            None => base.children_mut().push(other_child.clone()),
+            Some(existing)
+                if existing.kind() == FileKind::Directory
+                    && other_child.kind() == FileKind::Directory =>
+            {
+                // 한쪽이라도 탐색하지 않은 directory면 합친 결과도 완전하지 않음
+                let unexpanded =
+                    existing.is_expanded() == false || other_child.is_expanded() == false;
+                overlay(existing, other_child, label);
+                existing.set_unexpanded(unexpanded);
+                existing.update_merkle_digest();
+            }
+            Some(existing)
+                if existing.kind() == other_child.kind()
+                    && existing.hash().is_some()
+                    && existing.hash() == other_child.hash() => {}
+            Some(_) => {
+                let mut conflicted = other_child.clone();
+                conflicted.rename(format!("{}~{}", other_child.file_name(), label));
+                base.children_mut().push(conflicted);
+            }
+        }
+    }
+
+    base.children_mut().sort_by(FileNode::canonical_order);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::file_sys::scan_test_dir;
+    use super::*;
+    use std::fs;
+
+    fn child_names(node: &FileNode) -> Vec<&str> {
+        node.children()
+            .iter()
+            .map(|child| child.file_name())
+            .collect()
+    }
+
+    #[test]
+    fn test_namespace_views() {
+        let test_dir = "test_root_namespace";
+        let _ = fs::remove_dir_all(test_dir);
+        for device in ["laptop", "desktop"] {
+            fs::create_dir_all(format!("{}/{}/docs", test_dir, device)).unwrap();
+            fs::write(format!("{}/{}/docs/same.txt", test_dir, device), b"same").unwrap();
+            fs::write(format!("{}/{}/notes.txt", test_dir, device), device).unwrap();
+        }
+        fs::write(format!("{}/laptop/docs/only.txt", test_dir), b"only").unwrap();
+
+        let laptop_id = Uuid::from_u128(0x1111_1111 << 96);
+        let desktop_id = Uuid::from_u128(0x2222_2222 << 96);
+        let id_fs_map = BTreeMap::from([
+            (laptop_id, scan_test_dir(&format!("{}/laptop", test_dir))),
+            (desktop_id, scan_test_dir(&format!("{}/desktop", test_dir))),
+        ]);
+        let id_spec_map = BTreeMap::from([(
+            laptop_id,
+            DeviceSpec {
+                os: "Mac OS".to_string(),
+                ..DeviceSpec::default()
+            },
+        )]);
+
+        let laptop_label = device_label(&laptop_id, id_spec_map.get(&laptop_id));
+        let desktop_label = device_label(&desktop_id, None);
+        assert_eq!(laptop_label, "mac-os-11111111");
+        assert_eq!(desktop_label, "22222222");
+
+        let labeled_spec = DeviceSpec {
+            os: "Mac OS".to_string(),
+            label: "work-laptop".to_string(),
+            ..DeviceSpec::default()
+        };
+        assert_eq!(device_label(&laptop_id, Some(&labeled_spec)), "work-laptop");
+
+        let by_device = build_namespace(&id_spec_map, &id_fs_map, NamespaceView::ByDevice);
+        assert_eq!(by_device.file_name(), "");
+        assert_eq!(child_names(&by_device), vec!["22222222", "mac-os-11111111"]);
+
+        // laptop이 먼저 겹쳐지므로 desktop의 notes.txt가 충돌 사본이 됨
+        let union = build_namespace(&id_spec_map, &id_fs_map, NamespaceView::Union);
+        assert_eq!(
+            child_names(&union),
+            vec!["docs", "notes.txt", "notes.txt~22222222"]
+        );
+        let docs = &union.children()[0];
+        assert_eq!(child_names(docs), vec!["only.txt", "same.txt"]);
+        assert!(docs.hash().is_some());
+    }
+}
@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+
+use super::codec::{Compression, Encoding, WireFormat};
+
+// master와 client가 주고받는 REST/websocket 형식의 version
+// 형식이 호환되지 않게 바뀌면 올리고, 더 이상 읽을 수 없는 version은 MIN_PROTOCOL_VERSION을 올려서 거절
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+// peer 사이의 file transfer 방식
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferProtocol {
+    Tcp, // 요청은 "<경로>\n", 응답은 "OK\n" 혹은 "ERR <사유>\n"로 시작
+}
+
+// 이전 client에는 없을 수 있는 기능
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    SubtreePatch, // 변경된 subtree만 전송
+    LazyExpand,   // 탐색하지 않은 directory를 요청받아 탐색
+    SharePolicy,  // 공개 범위가 표시된 tree
+    Telemetry,    // 주기적인 상태 보고
+}
+
+// 직렬화된 형식을 알 수 없는 값은 무시해서 새 version의 peer와도 공통 부분을 찾을 수 있도록 함
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub protocol_version: u32,
+    #[serde(default, deserialize_with = "known_values")]
+    pub encodings: Vec<Encoding>,
+    #[serde(default, deserialize_with = "known_values")]
+    pub compressions: Vec<Compression>,
+    #[serde(default, deserialize_with = "known_values")]
+    pub transfer_protocols: Vec<TransferProtocol>,
+    #[serde(default, deserialize_with = "known_values")]
+    pub features: Vec<Feature>,
+}
+
+// capabilities를 보내지 않는 이전 client는 json, 압축 없음만 지원하는 것으로 간주
+// 이전 client의 file transfer는 응답 형식이 달라서 호환되지 않음
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities {
+            protocol_version: 0,
+            encodings: vec![Encoding::Json],
+            compressions: vec![Compression::Identity],
+            transfer_protocols: Vec::new(),
+            features: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProtocolError {
+    UnsupportedVersion { version: u32, min: u32, max: u32 },
+    NoCommonEncoding,
+}
+
+impl std::fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProtocolError::UnsupportedVersion { version, min, max } => write!(
+                f,
+                "지원하지 않는 protocol version입니다: {} (지원 범위: {}~{}). client를 업데이트해주세요.",
+                version, min, max
+            ),
+            ProtocolError::NoCommonEncoding => {
+                write!(f, "공통으로 지원하는 직렬화 형식이 없습니다.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+// handshake에 대한 master의 응답, 거절된 경우 reason에 사유가 있음
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandshakeReply {
+    pub accepted: bool,
+    pub capabilities: Capabilities,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Capabilities {
+    // 이 build가 지원하는 항목, 선호하는 순서
+    pub fn current() -> Self {
+        Capabilities {
+            protocol_version: PROTOCOL_VERSION,
+            encodings: vec![Encoding::Cbor, Encoding::Json],
+            compressions: vec![Compression::Zstd, Compression::Gzip, Compression::Identity],
+            transfer_protocols: vec![TransferProtocol::Tcp],
+            features: vec![
+                Feature::SubtreePatch,
+                Feature::LazyExpand,
+                Feature::SharePolicy,
+                Feature::Telemetry,
+            ],
+        }
+    }
+
+    // 이 build와 통신할 수 있는 peer인지 확인
+    pub fn check_compatible(&self) -> Result<(), ProtocolError> {
+        if self.protocol_version < MIN_PROTOCOL_VERSION || self.protocol_version > PROTOCOL_VERSION
+        {
+            return Err(ProtocolError::UnsupportedVersion {
+                version: self.protocol_version,
+                min: MIN_PROTOCOL_VERSION,
+                max: PROTOCOL_VERSION,
+            });
+        }
+        if self
+            .common_with(&Capabilities::current())
+            .encodings
+            .is_empty()
+        {
+            return Err(ProtocolError::NoCommonEncoding);
+        }
+
+        Ok(())
+    }
+
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.features.contains(&feature)
+    }
+
+    // 양쪽이 모두 지원하는 항목, 순서는 self의 선호 순서를 따름
+    pub fn common_with(&self, other: &Capabilities) -> Capabilities {
+        Capabilities {
+            protocol_version: self.protocol_version.min(other.protocol_version),
+            encodings: intersect(&self.encodings, &other.encodings),
+            compressions: intersect(&self.compressions, &other.compressions),
+            transfer_protocols: intersect(&self.transfer_protocols, &other.transfer_protocols),
+            features: intersect(&self.features, &other.features),
+        }
+    }
+
+    // preferred를 peer가 지원하지 않으면 공통으로 지원하는 형식 중 선호 순서가 가장 높은 것을 사용
+    // 공통 형식이 없으면 모든 version이 읽을 수 있는 json, 압축 없음
+    pub fn choose_wire_format(&self, preferred: WireFormat, peer: &Capabilities) -> WireFormat {
+        let common = self.common_with(peer);
+
+        let encoding = match common.encodings.contains(&preferred.encoding) {
+            true => preferred.encoding,
+            false => common.encodings.first().copied().unwrap_or(Encoding::Json),
+        };
+        let compression = match common.compressions.contains(&preferred.compression) {
+            true => preferred.compression,
+            false => common
+                .compressions
+                .first()
+                .copied()
+                .unwrap_or(Compression::Identity),
+        };
+
+        WireFormat::new(encoding, compression)
+    }
+
+    pub fn choose_transfer_protocol(&self, peer: &Capabilities) -> Option<TransferProtocol> {
+        self.common_with(peer).transfer_protocols.first().copied()
+    }
+}
+
+fn intersect<T: Copy + PartialEq>(preferred: &[T], other: &[T]) -> Vec<T> {
+    preferred
+        .iter()
+        .filter(|value| other.contains(value))
+        .copied()
+        .collect()
+}
+
+fn known_values<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let values: Vec<serde_json::Value> = Vec::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|value| serde_json::from_value(value).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_compatible() {
+        assert!(Capabilities::current().check_compatible().is_ok());
+        assert_eq!(
+            Capabilities::default().check_compatible(),
+            Err(ProtocolError::UnsupportedVersion {
+                version: 0,
+                min: MIN_PROTOCOL_VERSION,
+                max: PROTOCOL_VERSION
+            })
+        );
+
+        let newer = Capabilities {
+            protocol_version: PROTOCOL_VERSION + 1,
+            ..Capabilities::current()
+        };
+        assert!(newer.check_compatible().is_err());
+
+        let no_encoding = Capabilities {
+            encodings: Vec::new(),
+            ..Capabilities::current()
+        };
+        assert_eq!(
+            no_encoding.check_compatible(),
+            Err(ProtocolError::NoCommonEncoding)
+        );
+    }
+
+    #[test]
+    fn test_negotiate_with_peer() {
+        let current = Capabilities::current();
+        let peer = Capabilities {
+            protocol_version: PROTOCOL_VERSION,
+            encodings: vec![Encoding::Json],
+            compressions: vec![Compression::Identity, Compression::Gzip],
+            transfer_protocols: Vec::new(),
+            features: vec![Feature::Telemetry],
+        };
+
+        let preferred = WireFormat::new(Encoding::Cbor, Compression::Zstd);
+        assert_eq!(
+            current.choose_wire_format(preferred, &peer),
+            WireFormat::new(Encoding::Json, Compression::Gzip)
+        );
+        assert_eq!(current.choose_wire_format(preferred, &current), preferred);
+        assert_eq!(current.choose_transfer_protocol(&peer), None);
+        assert_eq!(
+            current.choose_transfer_protocol(&current),
+            Some(TransferProtocol::Tcp)
+        );
+
+        let common = current.common_with(&peer);
+        assert!(common.supports(Feature::Telemetry));
+        assert!(common.supports(Feature::LazyExpand) == false);
+    }
+
+    #[test]
+    fn test_unknown_values_ignored() {
+        let text = r#"{
+            "protocol_version": 1,
+            "encodings": ["msgpack", "json"],
+            "compressions": ["brotli"],
+            "features": ["lazy_expand", "resume_transfer"]
+        }"#;
+        let capabilities: Capabilities = serde_json::from_str(text).unwrap();
+
+        assert_eq!(capabilities.encodings, vec![Encoding::Json]);
+        assert!(capabilities.compressions.is_empty());
+        assert!(capabilities.transfer_protocols.is_empty());
+        assert_eq!(capabilities.features, vec![Feature::LazyExpand]);
+    }
+}
@@ -0,0 +1,307 @@
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+use super::file_sys::{FileNode, FileSystem};
+use super::hash;
+use super::rel_path::{
+    join_path, normalize_name, normalize_relative_path, same_name, split_components,
+};
+
+const NODE_ID_LEN: usize = 16;
+
+// root 기준 상대경로로부터 계산되므로 다시 탐색해도 같은 entry는 같은 ID를 가짐
+pub fn node_id(relative_path: &str) -> String {
+    let normalized_path = split_path(relative_path).join("/");
+    hash::hash_bytes(normalized_path.as_bytes())[..NODE_ID_LEN].to_string()
+}
+
+// 모든 경로는 root 기준 상대경로이며 '/'로 구분 (rel_path 참고)
+impl FileSystem {
+    // "./a//b/"나 "a\\b"와 같은 입력도 "a/b"로 취급하고, 이름은 정규화 형식과 관계없이 비교
+    pub fn lookup(&self, relative_path: &str) -> Option<&FileNode> {
+        let mut current_node = &self.node;
+        for name in split_path(relative_path) {
+            current_node = current_node
+                .children()
+                .iter()
+                .find(|child| same_name(child.file_name(), &name))?;
+        }
+
+        Some(current_node)
+    }
+
+    // directory가 아니면 None
+    pub fn list_dir(&self, relative_path: &str) -> Option<Vec<String>> {
+        let dir_node = self.lookup(relative_path)?;
+        if dir_node.is_dir() == false {
+            return None;
+        }
+
+        let base_path = split_path(relative_path).join("/");
+        Some(
+            dir_node
+                .children()
+                .iter()
+                .map(|child| join_path(&base_path, &normalize_name(child.file_name())))
+                .collect(),
+        )
+    }
+
+    // root를 제외한 모든 entry를 전위 순회 순서로 반환
+    pub fn entries(&self) -> Vec<(String, &FileNode)> {
+        let mut entries: Vec<(String, &FileNode)> = Vec::new();
+        collect_entries(&self.node, "", &mut entries);
+        entries
+    }
+
+    // 절대경로 혹은 상대경로를 정규화된 root 기준 상대경로로 변환 (root 밖이거나 '..'가 포함되면 None)
+    pub fn relative_path_of(&self, path: &Path) -> Option<String> {
+        let relative_path = match path.strip_prefix(self.node.file_name()) {
+            Ok(relative_path) => relative_path,
+            Err(_) if path.is_absolute() => return None,
+            Err(_) => path,
+        };
+
+        normalize_relative_path(&relative_path.to_string_lossy())
+    }
+
+    // tree에 있는 entry는 disk에 저장된 이름을 사용
+    pub fn absolute_path_of(&self, relative_path: &str) -> PathBuf {
+        let mut abs_path = PathBuf::from(self.node.file_name());
+        let mut current_node = Some(&self.node);
+        for name in split_path(relative_path) {
+            let child = current_node.and_then(|node| {
+                node.children()
+                    .iter()
+                    .find(|child| same_name(child.file_name(), &name))
+            });
+            abs_path.push(child.map(|child| child.file_name()).unwrap_or(&name));
+            current_node = child;
+        }
+
+        abs_path
+    }
+
+    // tree에 존재하는 entry의 ID
+    pub fn id_of(&self, relative_path: &str) -> Option<String> {
+        self.lookup(relative_path)?;
+        Some(node_id(relative_path))
+    }
+
+    // ID에 해당하는 entry의 root 기준 상대경로
+    pub fn path_of(&self, id: &str) -> Option<String> {
+        if node_id("") == id {
+            return Some(String::new());
+        }
+
+        self.entries()
+            .into_iter()
+            .map(|(path, _)| path)
+            .find(|path| node_id(path) == id)
+    }
+
+    // ID 혹은 root 기준 상대경로로 entry를 찾아 (상대경로, node)를 반환
+    pub fn resolve_entry(&self, path_or_id: &str) -> Option<(String, &FileNode)> {
+        let relative_path = match self.lookup(path_or_id) {
+            Some(_) => split_path(path_or_id).join("/"),
+            None => self.path_of(path_or_id)?,
+        };
+        let node = self.lookup(&relative_path)?;
+
+        Some((relative_path, node))
+    }
+
+    // *, ?, [...]는 경로 구분자를 넘지 않고 **는 여러 단계의 directory와 일치
+    pub fn glob(&self, pattern: &str) -> Result<Vec<String>, regex::Error> {
+        let glob_regex = Regex::new(&glob_to_regex(pattern))?;
+
+        Ok(self
+            .entries()
+            .into_iter()
+            .map(|(path, _)| path)
+            .filter(|path| glob_regex.is_match(path))
+            .collect())
+    }
+
+    // 이름(file_name)이 정규식과 일치하는 entry
+    pub fn search(&self, pattern: &str) -> Result<Vec<String>, regex::Error> {
+        let name_regex = Regex::new(pattern)?;
+
+        Ok(self
+            .entries()
+            .into_iter()
+            .filter(|(_, node)| name_regex.is_match(&normalize_name(node.file_name())))
+            .map(|(path, _)| path)
+            .collect())
+    }
+}
+
+// '..'는 그대로 남기며, tree에 '..'라는 이름의 entry는 없으므로 lookup은 실패
+fn split_path(relative_path: &str) -> Vec<String> {
+    split_components(relative_path)
+        .map(normalize_name)
+        .collect()
+}
+
+fn collect_entries<'a>(
+    node: &'a FileNode,
+    base_path: &str,
+    entries: &mut Vec<(String, &'a FileNode)>,
+) {
+    for child in node.children() {
+        let child_path = join_path(base_path, &normalize_name(child.file_name()));
+        entries.push((child_path.clone(), child));
+        collect_entries(child, &child_path, entries);
+    }
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex_str = String::from("^");
+    let chars: Vec<char> = pattern.trim_matches('/').chars().collect();
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        match chars[idx] {
+            '*' if chars.get(idx + 1) == Some(&'*') => {
+                if chars.get(idx + 2) == Some(&'/') {
+                    // "**/"는 0개 이상의 directory
+                    regex_str.push_str("(?:.*/)?");
+                    idx += 3;
+                } else {
+                    regex_str.push_str(".*");
+                    idx += 2;
+                }
+                continue;
+            }
+            '*' => regex_str.push_str("[^/]*"),
+            '?' => regex_str.push_str("[^/]"),
+            '[' => match chars[idx..].iter().position(|c| *c == ']') {
+                Some(end) if end > 1 => {
+                    let class: String = chars[idx + 1..idx + end].iter().collect();
+                    let class = match class.strip_prefix('!') {
+                        Some(negated) => format!("^{}", negated),
+                        None => class,
+                    };
+                    regex_str.push('[');
+                    regex_str.push_str(&class.replace('\\', "\\\\"));
+                    regex_str.push(']');
+                    idx += end;
+                }
+                _ => regex_str.push_str("\\["),
+            },
+            c => regex_str.push_str(&regex::escape(&c.to_string())),
+        }
+        idx += 1;
+    }
+
+    regex_str.push('$');
+    regex_str
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn setup_query_directory(test_dir: &str) -> FileSystem {
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/docs/2024", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/src", test_dir)).unwrap();
+        fs::write(format!("{}/README.md", test_dir), b"").unwrap();
+        fs::write(format!("{}/docs/guide.md", test_dir), b"").unwrap();
+        fs::write(format!("{}/docs/2024/report.md", test_dir), b"").unwrap();
+        fs::write(format!("{}/src/main.rs", test_dir), b"").unwrap();
+
+        let mut file_system = FileSystem::new(test_dir).unwrap();
+        file_system.init_file_node().unwrap();
+        file_system
+    }
+
+    fn sorted(mut paths: Vec<String>) -> Vec<String> {
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_lookup_and_list_dir() {
+        let fs = setup_query_directory("test_root_query_lookup");
+
+        assert_eq!(fs.lookup("./docs//2024/").unwrap().file_name(), "2024");
+        assert!(fs.lookup("docs/missing.md").is_none());
+        assert_eq!(fs.lookup("").unwrap().file_name(), "test_root_query_lookup");
+
+        assert_eq!(
+            sorted(fs.list_dir("docs").unwrap()),
+            vec!["docs/2024".to_string(), "docs/guide.md".to_string()]
+        );
+        assert!(fs.list_dir("README.md").is_none());
+    }
+
+    #[test]
+    fn test_glob() {
+        let fs = setup_query_directory("test_root_query_glob");
+
+        assert_eq!(
+            sorted(fs.glob("*.md").unwrap()),
+            vec!["README.md".to_string()]
+        );
+        assert_eq!(
+            sorted(fs.glob("**/*.md").unwrap()),
+            vec![
+                "README.md".to_string(),
+                "docs/2024/report.md".to_string(),
+                "docs/guide.md".to_string()
+            ]
+        );
+        assert_eq!(
+            sorted(fs.glob("docs/*").unwrap()),
+            vec!["docs/2024".to_string(), "docs/guide.md".to_string()]
+        );
+        assert_eq!(
+            sorted(fs.glob("src/ma?n.[a-z]s").unwrap()),
+            vec!["src/main.rs".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_node_id_and_path() {
+        let fs = setup_query_directory("test_root_query_node_id");
+
+        let report_id = fs.id_of("docs/2024/report.md").unwrap();
+        assert_eq!(report_id, node_id("./docs//2024/report.md"));
+        assert_eq!(report_id.len(), NODE_ID_LEN);
+        assert_eq!(fs.path_of(&report_id).unwrap(), "docs/2024/report.md");
+        assert!(fs.id_of("docs/missing.md").is_none());
+
+        // 다시 탐색해도 ID는 유지
+        let rescanned = setup_query_directory("test_root_query_node_id");
+        assert_eq!(rescanned.id_of("docs/2024/report.md").unwrap(), report_id);
+
+        let (path, node) = fs.resolve_entry(&report_id).unwrap();
+        assert_eq!(path, "docs/2024/report.md");
+        assert_eq!(node.file_name(), "report.md");
+        assert_eq!(fs.resolve_entry("src/main.rs").unwrap().0, "src/main.rs");
+
+        let abs_path = fs.absolute_path_of("src/main.rs");
+        assert_eq!(
+            fs.relative_path_of(&abs_path).unwrap(),
+            "src/main.rs".to_string()
+        );
+        assert!(fs.relative_path_of(Path::new("../etc/passwd")).is_none());
+        assert!(fs.relative_path_of(Path::new("/etc/passwd")).is_none());
+    }
+
+    #[test]
+    fn test_search() {
+        let fs = setup_query_directory("test_root_query_search");
+
+        assert_eq!(
+            sorted(fs.search(r"^(guide|report)\.md$").unwrap()),
+            vec![
+                "docs/2024/report.md".to_string(),
+                "docs/guide.md".to_string()
+            ]
+        );
+        assert!(fs.search("(").is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use unicode_normalization::UnicodeNormalization;
+
+use super::file_sys::{FileNode, FileSystem};
+
+// group의 device들이 같은 entry를 같은 문자열로 표현하도록 이름은 NFC로 정규화 (macOS는 NFD로 저장)
+// 경로는 root 기준 상대경로이며 운영체제와 관계없이 '/'로 구분
+pub fn normalize_name(name: &str) -> String {
+    name.nfc().collect()
+}
+
+// 정규화 형식만 다른 이름은 같은 이름
+pub fn same_name(a: &str, b: &str) -> bool {
+    a == b || a.nfc().eq(b.nfc())
+}
+
+// 대소문자를 구분하지 않는 file system(Windows, macOS 기본값)에서 같은 entry가 되는 이름은 같은 key
+pub fn case_fold_key(name: &str) -> String {
+    name.nfc()
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .nfc()
+        .collect()
+}
+
+// '/'와 '\'를 모두 구분자로 허용해서 "a/b" 형태로 변환
+// '..'나 drive prefix("C:")가 포함되면 root 밖을 가리킬 수 있으므로 None
+pub fn normalize_relative_path(path: &str) -> Option<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in split_components(path) {
+        if name == ".." || is_drive_prefix(name) {
+            return None;
+        }
+        names.push(normalize_name(name));
+    }
+
+    Some(names.join("/"))
+}
+
+// 빈 component와 '.'은 제외
+pub(crate) fn split_components(path: &str) -> impl Iterator<Item = &str> {
+    path.split(['/', '\\'])
+        .filter(|name| name.is_empty() == false && *name != ".")
+}
+
+pub(crate) fn join_path(base_path: &str, name: &str) -> String {
+    if base_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", base_path, name)
+    }
+}
+
+fn is_drive_prefix(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    bytes.len() == 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+impl FileSystem {
+    // 같은 directory에서 대소문자 혹은 정규화 형식만 다른 entry들의 상대경로 묶음
+    // 대소문자를 구분하지 않는 device에는 묶음마다 하나만 저장할 수 있음
+    pub fn case_collisions(&self) -> Vec<Vec<String>> {
+        let mut collisions: Vec<Vec<String>> = Vec::new();
+        collect_collisions(&self.node, "", &mut collisions);
+        collisions
+    }
+}
+
+fn collect_collisions(node: &FileNode, base_path: &str, collisions: &mut Vec<Vec<String>>) {
+    let mut folded_names: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for child in node.children() {
+        let child_path = join_path(base_path, &normalize_name(child.file_name()));
+        folded_names
+            .entry(case_fold_key(child.file_name()))
+            .or_default()
+            .push(child_path.clone());
+        collect_collisions(child, &child_path, collisions);
+    }
+
+    collisions.extend(folded_names.into_values().filter(|paths| paths.len() > 1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const NFC_NAME: &str = "\u{d55c}\u{ae00}.txt"; // 한글.txt
+    const NFD_NAME: &str = "\u{1112}\u{1161}\u{11ab}\u{1100}\u{1173}\u{11af}.txt";
+
+    #[test]
+    fn test_normalize_relative_path() {
+        assert_eq!(
+            normalize_relative_path("docs\\2024/./report.md"),
+            Some("docs/2024/report.md".to_string())
+        );
+        assert_eq!(
+            normalize_relative_path(&format!("/docs//{}", NFD_NAME)),
+            Some(format!("docs/{}", NFC_NAME))
+        );
+        assert_eq!(normalize_relative_path(""), Some(String::new()));
+        assert_eq!(normalize_relative_path("docs\\..\\..\\secret"), None);
+        assert_eq!(normalize_relative_path("C:\\Windows\\win.ini"), None);
+
+        assert!(same_name(NFC_NAME, NFD_NAME));
+        assert_eq!(case_fold_key("README.md"), case_fold_key("readme.MD"));
+    }
+
+    #[test]
+    fn test_lookup_across_platforms() {
+        let test_dir = "test_root_rel_path";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/Docs", test_dir)).unwrap();
+        fs::write(format!("{}/Docs/{}", test_dir, NFD_NAME), b"nfd").unwrap();
+        fs::write(format!("{}/Docs/readme.md", test_dir), b"lower").unwrap();
+        // 대소문자를 구분하지 않는 file system(macOS, Windows)에서는 같은 파일을 덮어씀
+        #[cfg(target_os = "linux")]
+        fs::write(format!("{}/Docs/README.md", test_dir), b"upper").unwrap();
+
+        let mut file_system = FileSystem::new(test_dir).unwrap();
+        file_system.init_file_node().unwrap();
+
+        // macOS에서 등록된 NFD 이름을 Windows 형식의 NFC 경로로 요청
+        let requested_path = format!("Docs\\{}", NFC_NAME);
+        assert!(file_system.lookup(&requested_path).is_some());
+        assert_eq!(
+            file_system.relative_path_of(std::path::Path::new(&requested_path)),
+            Some(format!("Docs/{}", NFC_NAME))
+        );
+        assert!(file_system.absolute_path_of(&requested_path).exists());
+
+        // master에 등록되는 tree의 이름은 NFC
+        let published = file_system.published();
+        let published_names: Vec<&str> = published.node.children()[0]
+            .children()
+            .iter()
+            .map(|child| child.file_name())
+            .collect();
+        assert!(published_names.contains(&NFC_NAME));
+
+        #[cfg(target_os = "linux")]
+        assert_eq!(
+            file_system.case_collisions(),
+            vec![vec![
+                "Docs/README.md".to_string(),
+                "Docs/readme.md".to_string()
+            ]]
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use chrono::{Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use super::file_sys::{format_size, FileKind, FileNode};
+
+const BRANCH: &str = "├── ";
+const LAST_BRANCH: &str = "└── ";
+const VERTICAL: &str = "│   ";
+const BLANK: &str = "    ";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    #[default]
+    Name, // directory 먼저, 이름순 (tree에 저장된 순서)
+    Size,
+    Modified,
+}
+
+// tree 명령어처럼 깊이 우선으로 출력
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TreeRenderer {
+    pub max_depth: Option<usize>, // root의 하위 entry가 깊이 1, None이면 제한 없음
+    pub max_children: Option<usize>, // directory마다 출력할 최대 entry 수, None이면 제한 없음
+    pub show_size: bool,
+    pub show_modified: bool,
+    pub sort_by: SortBy,
+    pub reverse: bool,
+}
+
+impl Default for TreeRenderer {
+    fn default() -> Self {
+        TreeRenderer {
+            max_depth: None,
+            max_children: None,
+            show_size: true,
+            show_modified: false,
+            sort_by: SortBy::Name,
+            reverse: false,
+        }
+    }
+}
+
+// 이름 부분과 size, 수정 시각 column
+struct Row {
+    tree: String,
+    size: String,
+    modified: String,
+}
+
+impl TreeRenderer {
+    pub fn render(&self, root: &FileNode) -> String {
+        let mut rows: Vec<Row> = vec![self.row(root, String::new())];
+        self.collect_rows(root, "", 1, &mut rows);
+
+        // column을 맞추기 위해 가장 긴 이름 기준으로 정렬
+        let tree_width = rows
+            .iter()
+            .map(|row| row.tree.chars().count())
+            .max()
+            .unwrap_or(0);
+        let size_width = rows
+            .iter()
+            .map(|row| row.size.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut rendered = String::new();
+        for row in rows {
+            let mut line = row.tree;
+            if self.show_size {
+                line = format!("{:<tree_width$}  {:>size_width$}", line, row.size);
+            }
+            if self.show_modified {
+                let line_width = match self.show_size {
+                    true => tree_width + 2 + size_width,
+                    false => tree_width,
+                };
+                line = format!("{:<line_width$}  {}", line, row.modified);
+            }
+
+            rendered.push_str(line.trim_end());
+            rendered.push('\n');
+        }
+
+        rendered
+    }
+
+    fn collect_rows(&self, node: &FileNode, prefix: &str, depth: usize, rows: &mut Vec<Row>) {
+        if node.children().is_empty() {
+            return;
+        }
+        if let Some(max_depth) = self.max_depth {
+            if depth > max_depth {
+                return;
+            }
+        }
+
+        let children = self.sorted_children(node);
+        let shown_count = match self.max_children {
+            Some(max_children) => children.len().min(max_children),
+            None => children.len(),
+        };
+        let hidden_count = children.len() - shown_count;
+
+        for (idx, child) in children.iter().take(shown_count).enumerate() {
+            let is_last = idx + 1 == shown_count && hidden_count == 0;
+            let (branch, child_prefix) = match is_last {
+                true => (LAST_BRANCH, format!("{}{}", prefix, BLANK)),
+                false => (BRANCH, format!("{}{}", prefix, VERTICAL)),
+            };
+
+            rows.push(self.row(child, format!("{}{}", prefix, branch)));
+            self.collect_rows(child, &child_prefix, depth + 1, rows);
+        }
+
+        if hidden_count > 0 {
+            rows.push(Row {
+                tree: format!("{}{}... ({}개 생략)", prefix, LAST_BRANCH, hidden_count),
+                size: String::new(),
+                modified: String::new(),
+            });
+        }
+    }
+
+    fn sorted_children<'a>(&self, node: &'a FileNode) -> Vec<&'a FileNode> {
+        let mut children: Vec<&FileNode> = node.children().iter().collect();
+
+        match self.sort_by {
+            SortBy::Name => children.sort_by(|a, b| FileNode::canonical_order(a, b)),
+            SortBy::Size => children.sort_by(|a, b| {
+                a.size()
+                    .cmp(&b.size())
+                    .then_with(|| FileNode::canonical_order(a, b))
+            }),
+            SortBy::Modified => children.sort_by(|a, b| {
+                a.modified()
+                    .cmp(&b.modified())
+                    .then_with(|| FileNode::canonical_order(a, b))
+            }),
+        }
+        if self.reverse {
+            children.reverse();
+        }
+
+        children
+    }
+
+    fn row(&self, node: &FileNode, branch: String) -> Row {
+        let tree = match node.kind() {
+            FileKind::Directory if node.is_expanded() == false => {
+                format!("{}{}/ ...", branch, node.file_name())
+            }
+            FileKind::Directory => format!("{}{}/", branch, node.file_name()),
+            FileKind::Symlink => match node.link_target() {
+                Some(link_target) => format!("{}{}@ -> {}", branch, node.file_name(), link_target),
+                None => format!("{}{}@", branch, node.file_name()),
+            },
+            FileKind::File => format!("{}{}", branch, node.file_name()),
+        };
+
+        let size = match node.kind() {
+            FileKind::File => format_size(node.size()),
+            _ => String::new(),
+        };
+
+        let modified = node
+            .modified()
+            .and_then(|secs| Local.timestamp_opt(secs as i64, 0).single())
+            .map(|datetime| datetime.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+
+        Row {
+            tree,
+            size,
+            modified,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::file_sys::{scan_test_dir, FileSystem};
+    use super::*;
+    use std::fs;
+
+    fn scan(test_dir: &str) -> FileSystem {
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/docs/nested", test_dir)).unwrap();
+        fs::write(format!("{}/docs/nested/deep.txt", test_dir), b"").unwrap();
+        fs::write(format!("{}/docs/guide.md", test_dir), vec![0u8; 2048]).unwrap();
+        fs::write(format!("{}/a.txt", test_dir), b"a").unwrap();
+        fs::write(format!("{}/b.txt", test_dir), b"bbb").unwrap();
+
+        scan_test_dir(test_dir)
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let fs = scan("test_root_render_tree");
+        let renderer = TreeRenderer {
+            show_size: false,
+            ..TreeRenderer::default()
+        };
+
+        assert_eq!(
+            renderer.render(&fs.node),
+            [
+                "test_root_render_tree/",
+                "├── docs/",
+                "│   ├── nested/",
+                "│   │   └── deep.txt",
+                "│   └── guide.md",
+                "├── a.txt",
+                "└── b.txt",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_render_limits_and_sort() {
+        let fs = scan("test_root_render_limits");
+        let renderer = TreeRenderer {
+            max_depth: Some(1),
+            max_children: Some(2),
+            sort_by: SortBy::Size,
+            reverse: true,
+            ..TreeRenderer::default()
+        };
+
+        let rendered = renderer.render(&fs.node);
+        let lines: Vec<&str> = rendered.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert!(rendered.contains("nested") == false);
+        assert_eq!(lines[3], "└── ... (1개 생략)");
+
+        // 큰 파일이 먼저 출력되고 size column은 오른쪽 정렬
+        let renderer = TreeRenderer {
+            max_depth: Some(1),
+            sort_by: SortBy::Size,
+            reverse: true,
+            ..TreeRenderer::default()
+        };
+        let rendered = renderer.render(&fs.node);
+        let b_line = rendered.lines().position(|line| line.contains("b.txt"));
+        let a_line = rendered.lines().position(|line| line.contains("a.txt"));
+        assert!(b_line < a_line);
+        assert!(rendered
+            .lines()
+            .any(|line| line.contains("b.txt") && line.ends_with(" 3 B")));
+    }
+}
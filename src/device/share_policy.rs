@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::file_sys::{FileNode, FileSystem, SubtreeUpdate};
+use super::rel_path::{join_path, normalize_name, normalize_relative_path};
+
+// 공유 중인 root 아래 subtree를 group에 어떻게 공개할지
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SharePolicy {
+    Hidden,     // master에 등록하지 않음
+    ListedOnly, // tree에는 보이지만 전송하지 않음
+    #[default]
+    Downloadable,
+}
+
+impl SharePolicy {
+    pub fn is_downloadable(&self) -> bool {
+        *self == SharePolicy::Downloadable
+    }
+}
+
+// root 기준 상대경로 -> policy, 가장 가까운 상위 경로의 policy가 적용됨 ("" 혹은 "."은 root)
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(from = "BTreeMap<String, SharePolicy>")]
+pub struct SharePolicies {
+    rules: BTreeMap<String, SharePolicy>,
+}
+
+impl SharePolicies {
+    pub fn new(rules: BTreeMap<String, SharePolicy>) -> Self {
+        let mut normalized_rules = BTreeMap::new();
+        for (path, policy) in rules {
+            match normalize_relative_path(&path) {
+                Some(normalized_path) => {
+                    normalized_rules.insert(normalized_path, policy);
+                }
+                None => log::warn!("root 밖을 가리키는 share 경로는 무시합니다: {:?}", path),
+            }
+        }
+
+        SharePolicies {
+            rules: normalized_rules,
+        }
+    }
+
+    // root 밖을 가리키는 경로는 hidden
+    pub fn policy_of(&self, relative_path: &str) -> SharePolicy {
+        let mut current_path = match normalize_relative_path(relative_path) {
+            Some(current_path) => current_path,
+            None => return SharePolicy::Hidden,
+        };
+        loop {
+            if let Some(policy) = self.rules.get(&current_path) {
+                return *policy;
+            }
+            match current_path.rfind('/') {
+                Some(idx) => current_path.truncate(idx),
+                None if current_path.is_empty() == false => current_path.clear(),
+                None => return SharePolicy::default(),
+            }
+        }
+    }
+
+    // hidden인 subtree를 제외하고 listed-only인 entry를 표시한 사본 (node는 base_path에 위치)
+    // root를 제외한 이름은 NFC로 정규화하며, base_path 자체가 hidden이면 None
+    pub fn publish(&self, node: &FileNode, base_path: &str) -> Option<FileNode> {
+        let policy = self.policy_of(base_path);
+        if policy == SharePolicy::Hidden {
+            return None;
+        }
+
+        let mut published = node.clone();
+        if base_path.is_empty() == false {
+            published.rename(normalize_name(node.file_name()));
+        }
+        published.set_share_policy(policy);
+        published.children_mut().clear();
+        for child in node.children() {
+            let child_path = join_path(base_path, &normalize_name(child.file_name()));
+            if let Some(published_child) = self.publish(child, &child_path) {
+                published.children_mut().push(published_child);
+            }
+        }
+        // NFC로 바꾼 이름은 원래 이름과 순서가 다를 수 있음
+        published.children_mut().sort_by(FileNode::canonical_order);
+
+        // 제외된 entry가 digest에 반영되지 않도록 다시 계산
+        published.update_merkle_digest();
+        Some(published)
+    }
+}
+
+impl From<BTreeMap<String, SharePolicy>> for SharePolicies {
+    fn from(rules: BTreeMap<String, SharePolicy>) -> Self {
+        SharePolicies::new(rules)
+    }
+}
+
+impl FileSystem {
+    // group에 공개되는 tree (master에는 이 tree만 등록)
+    pub fn published(&self) -> FileSystem {
+        let mut published = self.clone();
+        published.node = self
+            .share_policies()
+            .publish(&self.node, "")
+            .unwrap_or_else(|| {
+                let mut empty_root = self.node.clone();
+                empty_root.children_mut().clear();
+                empty_root.update_merkle_digest();
+                empty_root
+            });
+        published
+    }
+
+    // master에 전달할 변경분, 변경된 subtree가 hidden이면 None
+    pub fn publish_update(&self, update: SubtreeUpdate) -> Option<SubtreeUpdate> {
+        let path: Vec<String> = update
+            .path
+            .iter()
+            .map(|name| normalize_name(name))
+            .collect();
+        let node = self
+            .share_policies()
+            .publish(&update.node, &path.join("/"))?;
+        Some(SubtreeUpdate { path, node })
+    }
+
+    pub fn share_policy_of(&self, relative_path: &str) -> SharePolicy {
+        self.share_policies().policy_of(relative_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_policy_of_nearest_ancestor() {
+        let policies = SharePolicies::new(BTreeMap::from([
+            ("private".to_string(), SharePolicy::Hidden),
+            ("movies/".to_string(), SharePolicy::ListedOnly),
+            ("./movies/trailers".to_string(), SharePolicy::Downloadable),
+        ]));
+
+        assert_eq!(policies.policy_of(""), SharePolicy::Downloadable);
+        assert_eq!(policies.policy_of("private/key.pem"), SharePolicy::Hidden);
+        assert_eq!(
+            policies.policy_of("privateer.txt"),
+            SharePolicy::Downloadable
+        );
+        assert_eq!(policies.policy_of("movies/a.mkv"), SharePolicy::ListedOnly);
+        assert_eq!(
+            policies.policy_of("movies/trailers/a.mp4"),
+            SharePolicy::Downloadable
+        );
+    }
+
+    #[test]
+    fn test_published_tree() {
+        let test_dir = "test_root_share_policy";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/private", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/movies", test_dir)).unwrap();
+        fs::write(format!("{}/private/key.pem", test_dir), b"secret").unwrap();
+        fs::write(format!("{}/movies/a.mkv", test_dir), b"movie").unwrap();
+        fs::write(format!("{}/readme.txt", test_dir), b"readme").unwrap();
+
+        let mut file_system = FileSystem::new(test_dir).unwrap();
+        file_system.set_share_policies(SharePolicies::new(BTreeMap::from([
+            ("private".to_string(), SharePolicy::Hidden),
+            ("movies".to_string(), SharePolicy::ListedOnly),
+        ])));
+        file_system.init_file_node().unwrap();
+
+        // local tree에는 모두 남아있음
+        assert!(file_system.lookup("private/key.pem").is_some());
+
+        let published = file_system.published();
+        assert!(published.lookup("private").is_none());
+        assert_eq!(
+            published.lookup("movies/a.mkv").unwrap().share_policy(),
+            SharePolicy::ListedOnly
+        );
+        assert_eq!(
+            published.lookup("readme.txt").unwrap().share_policy(),
+            SharePolicy::Downloadable
+        );
+        assert!(published.node.hash() != file_system.node.hash());
+
+        fs::write(format!("{}/private/other.pem", test_dir), b"secret").unwrap();
+        let update = file_system.refresh_subtree(Path::new("private")).unwrap();
+        assert!(file_system.publish_update(update).is_none());
+    }
+
+    #[test]
+    fn test_published_tree_nfd_names() {
+        // 대소문자만 다른 이름은 codepoint로 비교하므로 NFD에서는 "e\u{301}" < "\u{c9}"이지만 NFC에서는 "\u{c9}" < "\u{e9}"
+        let nfd_dir = "test_root_share_policy_nfd";
+        let nfc_dir = "test_root_share_policy_nfc";
+        for (test_dir, name) in [(nfd_dir, "e\u{301}.txt"), (nfc_dir, "\u{e9}.txt")] {
+            let _ = fs::remove_dir_all(test_dir);
+            fs::create_dir_all(test_dir).unwrap();
+            fs::write(format!("{}/{}", test_dir, name), b"accent").unwrap();
+            fs::write(format!("{}/\u{c9}.txt", test_dir), b"upper").unwrap();
+        }
+
+        let mut nfd_fs = FileSystem::new(nfd_dir).unwrap();
+        nfd_fs.init_file_node().unwrap();
+        let mut nfc_fs = FileSystem::new(nfc_dir).unwrap();
+        nfc_fs.init_file_node().unwrap();
+
+        let nfd_published = nfd_fs.published();
+        let nfc_published = nfc_fs.published();
+        let names: Vec<&str> = nfd_published
+            .node
+            .children()
+            .iter()
+            .map(|child| child.file_name())
+            .collect();
+        assert_eq!(names, vec!["\u{c9}.txt", "\u{e9}.txt"]);
+        assert!(nfd_published.node.hash().is_some());
+        assert_eq!(nfd_published.node.hash(), nfc_published.node.hash());
+
+        fs::remove_dir_all(nfd_dir).unwrap();
+        fs::remove_dir_all(nfc_dir).unwrap();
+    }
+}
@@ -1,10 +1,113 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+use super::endpoint::Endpoint;
+use super::protocol::Capabilities;
+
+// 이전 client가 보낸 spec도 읽을 수 있도록 추가된 항목은 기본값을 가짐
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct DeviceSpec {
-    pub ip: String,
+    pub ip: String, // master가 요청의 주소로 채움 (신뢰하는 proxy를 거치면 X-Forwarded-For)
     pub os: String,
     pub os_version: String,
-    pub listen_port: String,
+    #[serde(deserialize_with = "port_from_number_or_string")]
+    pub listen_port: u16, // 이전 client는 문자열로 보냄
+    #[serde(default)]
+    pub hostname: String,
+    #[serde(default)]
+    pub arch: String, // e.g. x86_64, aarch64
+    #[serde(default)]
+    pub cpu_cores: usize,
+    #[serde(default)]
+    pub total_memory: u64, // byte 단위
+    #[serde(default)]
+    pub free_memory: u64,
+    #[serde(default)]
+    pub free_disk: Option<u64>, // 공유 중인 root가 있는 volume의 여유 공간
+    #[serde(default)]
+    pub client_version: String,
+    #[serde(default)]
+    pub label: String, // 사용자가 정한 이름, group 안에서 유일
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub capabilities: Capabilities, // 없으면 handshake 이전의 client
+    #[serde(default)]
+    pub endpoints: Vec<Endpoint>, // peer가 file transfer 연결을 시도할 주소 후보
+}
+
+// 이전 client의 spec도 읽어서 handshake 이전의 client로 거절할 수 있도록 문자열도 허용
+fn port_from_number_or_string<'de, D>(deserializer: D) -> Result<u16, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Port {
+        Number(u16),
+        Text(String),
+    }
+
+    match Port::deserialize(deserializer)? {
+        Port::Number(port) => Ok(port),
+        Port::Text(port) => port.trim().parse().map_err(serde::de::Error::custom),
+    }
+}
+
+// mount point와 여유 공간 목록 중 path가 속한 volume(가장 긴 mount point)의 여유 공간
+pub fn volume_free_space(volumes: &[(PathBuf, u64)], path: &Path) -> Option<u64> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+    volumes
+        .iter()
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+        .map(|(_, free_space)| *free_space)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_free_space() {
+        let volumes = vec![
+            (PathBuf::from("/"), 100),
+            (PathBuf::from("/home"), 200),
+            (PathBuf::from("/home/user/external"), 300),
+        ];
+
+        assert_eq!(
+            volume_free_space(&volumes, Path::new("/home/user/docs")),
+            Some(200)
+        );
+        assert_eq!(
+            volume_free_space(&volumes, Path::new("/home/user/external/movies")),
+            Some(300)
+        );
+        assert_eq!(
+            volume_free_space(&volumes, Path::new("/var/tmp")),
+            Some(100)
+        );
+        // 이름의 앞부분만 같은 mount point는 다른 volume
+        assert_eq!(
+            volume_free_space(&volumes, Path::new("/homework")),
+            Some(100)
+        );
+        assert_eq!(volume_free_space(&[], Path::new("/var/tmp")), None);
+    }
+
+    #[test]
+    fn test_baseline_spec() {
+        let text = r#"{"ip":"","os":"linux","os_version":"6.1","listen_port":"8081"}"#;
+        let spec: DeviceSpec = serde_json::from_str(text).unwrap();
+
+        assert_eq!(spec.listen_port, 8081);
+        assert_eq!(spec.capabilities, Capabilities::default());
+        assert!(spec.capabilities.check_compatible().is_err());
+
+        let text = r#"{"ip":"","os":"linux","os_version":"6.1","listen_port":"port"}"#;
+        assert!(serde_json::from_str::<DeviceSpec>(text).is_err());
+    }
 }
@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use super::file_sys::{FileKind, FileNode, FileSystem};
+use super::namespace::device_label;
+use super::spec::DeviceSpec;
+
+const LARGEST_FILES_LEN: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub path: String, // root 기준 상대경로, group 합계에서는 device label로 시작
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtensionStats {
+    pub file_count: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsStats {
+    pub total_bytes: u64, // 파일 크기의 합
+    pub file_count: u64,
+    pub dir_count: u64, // root 제외
+    pub symlink_count: u64,
+    pub largest_files: Vec<FileEntry>, // 크기가 큰 순서
+    pub by_extension: BTreeMap<String, ExtensionStats>, // 소문자 확장자, 확장자가 없으면 ""
+    pub is_partial: bool, // 아직 탐색하지 않은 directory가 있어서 실제보다 작을 수 있음
+}
+
+impl FsStats {
+    // other의 경로 앞에 prefix를 붙여서 합침
+    pub fn merge(&mut self, other: &FsStats, prefix: &str) {
+        self.total_bytes += other.total_bytes;
+        self.file_count += other.file_count;
+        self.dir_count += other.dir_count;
+        self.symlink_count += other.symlink_count;
+        self.is_partial |= other.is_partial;
+
+        for (extension, other_extension) in &other.by_extension {
+            let extension_stats = self.by_extension.entry(extension.clone()).or_default();
+            extension_stats.file_count += other_extension.file_count;
+            extension_stats.total_bytes += other_extension.total_bytes;
+        }
+
+        for entry in &other.largest_files {
+            self.push_largest(FileEntry {
+                path: format!("{}/{}", prefix, entry.path),
+                size: entry.size,
+            });
+        }
+    }
+
+    fn push_largest(&mut self, entry: FileEntry) {
+        let idx = self
+            .largest_files
+            .iter()
+            .position(|largest| largest.size < entry.size)
+            .unwrap_or(self.largest_files.len());
+
+        if idx < LARGEST_FILES_LEN {
+            self.largest_files.insert(idx, entry);
+            self.largest_files.truncate(LARGEST_FILES_LEN);
+        }
+    }
+
+    fn collect(&mut self, node: &FileNode, base_path: &str) {
+        for child in node.children() {
+            let child_path = match base_path.is_empty() {
+                true => child.file_name().to_string(),
+                false => format!("{}/{}", base_path, child.file_name()),
+            };
+
+            match child.kind() {
+                FileKind::Directory => {
+                    self.dir_count += 1;
+                    self.is_partial |= child.is_expanded() == false;
+                    self.collect(child, &child_path);
+                }
+                FileKind::Symlink => self.symlink_count += 1,
+                FileKind::File => {
+                    self.total_bytes += child.size();
+                    self.file_count += 1;
+
+                    let extension_stats = self
+                        .by_extension
+                        .entry(extension_of(child.file_name()))
+                        .or_default();
+                    extension_stats.file_count += 1;
+                    extension_stats.total_bytes += child.size();
+
+                    self.push_largest(FileEntry {
+                        path: child_path,
+                        size: child.size(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+// ".bashrc"처럼 '.'으로 시작하는 이름은 확장자가 없는 것으로 취급
+fn extension_of(file_name: &str) -> String {
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) if stem.is_empty() == false => extension.to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+impl FileSystem {
+    pub fn stats(&self) -> FsStats {
+        let mut stats = FsStats {
+            is_partial: self.node.is_expanded() == false,
+            ..FsStats::default()
+        };
+        stats.collect(&self.node, "");
+        stats
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceStats {
+    pub label: String,
+    pub stats: FsStats,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct GroupStats {
+    pub devices: BTreeMap<Uuid, DeviceStats>,
+    pub total: FsStats, // largest_files의 경로는 "<device-label>/..."
+}
+
+pub fn group_stats(
+    id_spec_map: &BTreeMap<Uuid, DeviceSpec>,
+    id_fs_map: &BTreeMap<Uuid, FileSystem>,
+) -> GroupStats {
+    let mut group_stats = GroupStats::default();
+
+    for (id, fs) in id_fs_map {
+        let label = device_label(id, id_spec_map.get(id));
+        let stats = fs.stats();
+
+        group_stats.total.merge(&stats, &label);
+        group_stats
+            .devices
+            .insert(*id, DeviceStats { label, stats });
+    }
+
+    group_stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::file_sys::scan_test_dir;
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_fs_stats() {
+        let test_dir = "test_root_stats";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/photos/2024", test_dir)).unwrap();
+        fs::write(format!("{}/photos/2024/a.JPG", test_dir), vec![0u8; 300]).unwrap();
+        fs::write(format!("{}/photos/b.jpg", test_dir), vec![0u8; 200]).unwrap();
+        fs::write(format!("{}/notes.txt", test_dir), vec![0u8; 10]).unwrap();
+        fs::write(format!("{}/.bashrc", test_dir), vec![0u8; 5]).unwrap();
+
+        let stats = scan_test_dir(test_dir).stats();
+
+        assert_eq!(stats.total_bytes, 515);
+        assert_eq!(stats.file_count, 4);
+        assert_eq!(stats.dir_count, 2);
+        assert!(stats.is_partial == false);
+        assert_eq!(
+            stats.largest_files[..2],
+            [
+                FileEntry {
+                    path: "photos/2024/a.JPG".to_string(),
+                    size: 300
+                },
+                FileEntry {
+                    path: "photos/b.jpg".to_string(),
+                    size: 200
+                },
+            ]
+        );
+        assert_eq!(
+            stats.by_extension["jpg"],
+            ExtensionStats {
+                file_count: 2,
+                total_bytes: 500
+            }
+        );
+        assert_eq!(stats.by_extension[""].file_count, 1);
+    }
+
+    #[test]
+    fn test_group_stats() {
+        let test_dir = "test_root_group_stats";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/first", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/second", test_dir)).unwrap();
+        fs::write(format!("{}/first/a.bin", test_dir), vec![0u8; 100]).unwrap();
+        fs::write(format!("{}/second/b.bin", test_dir), vec![0u8; 400]).unwrap();
+
+        let first_id = Uuid::from_u128(0x1111_1111 << 96);
+        let second_id = Uuid::from_u128(0x2222_2222 << 96);
+        let id_fs_map = BTreeMap::from([
+            (first_id, scan_test_dir(&format!("{}/first", test_dir))),
+            (second_id, scan_test_dir(&format!("{}/second", test_dir))),
+        ]);
+
+        let group_stats = group_stats(&BTreeMap::new(), &id_fs_map);
+
+        assert_eq!(group_stats.devices[&first_id].stats.total_bytes, 100);
+        assert_eq!(group_stats.devices[&second_id].label, "22222222");
+        assert_eq!(group_stats.total.total_bytes, 500);
+        assert_eq!(group_stats.total.by_extension["bin"].file_count, 2);
+        assert_eq!(group_stats.total.largest_files[0].path, "22222222/b.bin");
+    }
+}
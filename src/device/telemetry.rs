@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// master가 device마다 보관하는 최근 sample 수
+pub const TELEMETRY_HISTORY_LEN: usize = 60;
+
+// client가 websocket으로 주기적으로 보내는 상태
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Telemetry {
+    pub timestamp: u64,            // UNIX epoch 기준 초
+    pub free_disk: Option<u64>,    // 공유 중인 root가 있는 volume의 여유 공간
+    pub load_average: Option<f64>, // 1분 평균, 지원하지 않는 플랫폼(Windows)은 None
+    pub bytes_served: u64,         // client 시작 후 file transfer로 보낸 byte 수
+    pub uptime: u64,               // client가 실행된 시간(초)
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TelemetryHistory {
+    samples: VecDeque<Telemetry>, // 오래된 순서
+}
+
+impl TelemetryHistory {
+    pub fn push(&mut self, sample: Telemetry) {
+        if self.samples.len() == TELEMETRY_HISTORY_LEN {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn latest(&self) -> Option<&Telemetry> {
+        self.samples.back()
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &Telemetry> {
+        self.samples.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telemetry_history_capped() {
+        let mut history = TelemetryHistory::default();
+        assert!(history.latest().is_none());
+        assert!(history.is_empty());
+
+        for timestamp in 0..(TELEMETRY_HISTORY_LEN as u64 + 5) {
+            history.push(Telemetry {
+                timestamp,
+                ..Telemetry::default()
+            });
+        }
+
+        assert_eq!(history.len(), TELEMETRY_HISTORY_LEN);
+        assert_eq!(history.samples().next().unwrap().timestamp, 5);
+        assert_eq!(
+            history.latest().unwrap().timestamp,
+            TELEMETRY_HISTORY_LEN as u64 + 4
+        );
+    }
+}
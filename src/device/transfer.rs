@@ -0,0 +1,292 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use super::file_sys::FileSystem;
+use super::rel_path::normalize_relative_path;
+
+// file transfer 요청은 root 기준 상대경로 한 줄, 요청한 쪽은 이후 쓰기를 종료함
+const MAX_REQUEST_LEN: usize = 4096;
+
+// file transfer 응답의 첫 줄, 이후에는 파일 내용 혹은 실패 사유가 옴
+const REPLY_OK: &str = "OK\n";
+const REPLY_ERR: &str = "ERR ";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ServeError {
+    NotShared,       // 아직 공유 중인 file system이 없음
+    InvalidPath,     // '..'가 포함되거나 root 밖의 절대경로
+    NotFound,        // tree에 없는 entry
+    NotAFile,        // directory
+    NotDownloadable, // ignore rule 혹은 share policy로 전송하지 않는 entry
+    OutsideRoot,     // symlink 등을 따라가면 root 밖을 가리킴
+    Io(String),
+}
+
+impl std::fmt::Display for ServeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ServeError::NotShared => write!(f, "공유 중인 file system이 없습니다."),
+            ServeError::InvalidPath => write!(f, "root 기준 상대경로만 요청할 수 있습니다."),
+            ServeError::NotFound => write!(f, "공유 중인 tree에 존재하지 않는 경로입니다."),
+            ServeError::NotAFile => write!(f, "파일만 전송받을 수 있습니다."),
+            ServeError::NotDownloadable => write!(f, "전송이 허용되지 않은 파일입니다."),
+            ServeError::OutsideRoot => write!(f, "공유 중인 root 밖을 가리키는 경로입니다."),
+            ServeError::Io(e) => write!(f, "파일을 읽지 못했습니다: {}", e),
+        }
+    }
+}
+
+impl FileSystem {
+    // peer가 요청한 경로를 실제로 읽을 절대경로로 변환
+    // tree에 등록되어 있고 전송이 허용된 파일이며, 실제 경로가 root 안에 있어야 함
+    pub fn resolve_request(&self, requested_path: &str) -> Result<PathBuf, ServeError> {
+        let relative_path = self
+            .relative_path_of(Path::new(requested_path))
+            .ok_or(ServeError::InvalidPath)?;
+        if relative_path.is_empty() {
+            return Err(ServeError::NotAFile);
+        }
+
+        let node = self.lookup(&relative_path).ok_or(ServeError::NotFound)?;
+        if node.is_dir() {
+            return Err(ServeError::NotAFile);
+        }
+
+        let abs_path = self.absolute_path_of(&relative_path);
+        if self.is_ignored(&abs_path)
+            || self.share_policy_of(&relative_path).is_downloadable() == false
+        {
+            return Err(ServeError::NotDownloadable);
+        }
+
+        // 탐색 이후 상위 directory가 symlink로 바뀐 경우도 막기 위해 요청마다 확인
+        let canonical_root =
+            fs::canonicalize(self.node.file_name()).map_err(|e| ServeError::Io(e.to_string()))?;
+        let canonical_path = fs::canonicalize(&abs_path).map_err(|_| ServeError::NotFound)?;
+        let target_path = match canonical_path.strip_prefix(&canonical_root) {
+            Ok(target_path) => target_path,
+            Err(_) => return Err(ServeError::OutsideRoot),
+        };
+
+        // symlink가 root 안의 다른 entry를 가리키면 실제 대상의 ignore rule과 share policy도 확인
+        let target_relative_path = normalize_relative_path(&target_path.to_string_lossy())
+            .ok_or(ServeError::InvalidPath)?;
+        if self.is_ignored(&Path::new(self.node.file_name()).join(target_path))
+            || self
+                .share_policy_of(&target_relative_path)
+                .is_downloadable()
+                == false
+        {
+            return Err(ServeError::NotDownloadable);
+        }
+        if canonical_path.is_file() == false {
+            return Err(ServeError::NotAFile);
+        }
+
+        Ok(canonical_path)
+    }
+}
+
+pub fn request_line(requested_path: &str) -> Vec<u8> {
+    format!("{}\n", requested_path).into_bytes()
+}
+
+// '\n' 혹은 EOF까지 읽은 요청 경로, 경로가 너무 길거나 UTF-8이 아니면 InvalidPath
+pub fn read_request<R: Read>(reader: R) -> Result<String, ServeError> {
+    let mut request = Vec::new();
+    BufReader::new(reader.take(MAX_REQUEST_LEN as u64 + 1))
+        .read_until(b'\n', &mut request)
+        .map_err(|e| ServeError::Io(e.to_string()))?;
+
+    match request.last() {
+        Some(b'\n') => {
+            request.pop();
+            if request.last() == Some(&b'\r') {
+                request.pop();
+            }
+        }
+        _ if request.len() > MAX_REQUEST_LEN => return Err(ServeError::InvalidPath),
+        _ => {}
+    }
+
+    String::from_utf8(request).map_err(|_| ServeError::InvalidPath)
+}
+
+pub fn ok_reply_header() -> &'static [u8] {
+    REPLY_OK.as_bytes()
+}
+
+pub fn err_reply(error: &ServeError) -> Vec<u8> {
+    format!("{}{}\n", REPLY_ERR, error).into_bytes()
+}
+
+// 응답에서 파일 내용을 꺼내고, 실패 응답이면 peer가 보낸 사유를 반환
+pub fn parse_reply(reply: &[u8]) -> Result<&[u8], String> {
+    if let Some(payload) = reply.strip_prefix(REPLY_OK.as_bytes()) {
+        return Ok(payload);
+    }
+
+    match reply.strip_prefix(REPLY_ERR.as_bytes()) {
+        Some(reason) => Err(String::from_utf8_lossy(reason).trim_end().to_string()),
+        None => Err("알 수 없는 형식의 응답입니다.".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    use super::super::file_sys::scan_test_dir;
+    use super::super::share_policy::{SharePolicies, SharePolicy};
+
+    fn scan(test_dir: &str) -> FileSystem {
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/shared/docs", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/shared/movies", test_dir)).unwrap();
+        fs::write(format!("{}/secret.txt", test_dir), b"secret").unwrap();
+        fs::write(format!("{}/shared/docs/a.txt", test_dir), b"a").unwrap();
+        fs::write(format!("{}/shared/movies/b.mkv", test_dir), b"b").unwrap();
+        fs::write(format!("{}/shared/.xilersignore", test_dir), "*.swp\n").unwrap();
+        fs::write(format!("{}/shared/docs/c.swp", test_dir), b"c").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("../secret.txt", format!("{}/shared/escape", test_dir)).unwrap();
+
+        let mut file_system = scan_test_dir(&format!("{}/shared", test_dir));
+        file_system.set_share_policies(SharePolicies::new(BTreeMap::from([(
+            "movies".to_string(),
+            SharePolicy::ListedOnly,
+        )])));
+        file_system
+    }
+
+    #[test]
+    fn test_resolve_request() {
+        let test_dir = "test_root_resolve_request";
+        let fs = scan(test_dir);
+
+        let resolved = fs.resolve_request("docs/a.txt").unwrap();
+        assert!(resolved.is_absolute());
+        assert!(resolved.ends_with("shared/docs/a.txt"));
+        assert!(fs.resolve_request("./docs//a.txt").is_ok());
+        assert!(fs
+            .resolve_request(&format!("{}/shared/docs/a.txt", test_dir))
+            .is_ok());
+
+        assert_eq!(fs.resolve_request("docs"), Err(ServeError::NotAFile));
+        assert_eq!(fs.resolve_request(""), Err(ServeError::NotAFile));
+        assert_eq!(
+            fs.resolve_request("docs/none.txt"),
+            Err(ServeError::NotFound)
+        );
+        assert_eq!(
+            fs.resolve_request("movies/b.mkv"),
+            Err(ServeError::NotDownloadable)
+        );
+    }
+
+    #[test]
+    fn test_resolve_request_traversal() {
+        let test_dir = "test_root_resolve_traversal";
+        let fs = scan(test_dir);
+
+        for requested_path in [
+            "../secret.txt",
+            "docs/../../secret.txt",
+            "docs/../a.txt",
+            "/etc/passwd",
+            "/etc/shadow",
+        ] {
+            assert_eq!(
+                fs.resolve_request(requested_path),
+                Err(ServeError::InvalidPath),
+                "{}",
+                requested_path
+            );
+        }
+
+        // 탐색 후에 추가된 파일이나 ignore된 파일도 전송하지 않음
+        fs::write(format!("{}/shared/docs/new.txt", test_dir), b"new").unwrap();
+        assert_eq!(
+            fs.resolve_request("docs/new.txt"),
+            Err(ServeError::NotFound)
+        );
+        assert!(fs.resolve_request("docs/c.swp").is_err());
+
+        #[cfg(unix)]
+        assert_eq!(fs.resolve_request("escape"), Err(ServeError::OutsideRoot));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_request_symlink_to_hidden() {
+        let test_dir = "test_root_resolve_symlink_hidden";
+        let _ = fs::remove_dir_all(test_dir);
+        fs::create_dir_all(format!("{}/private", test_dir)).unwrap();
+        fs::create_dir_all(format!("{}/docs", test_dir)).unwrap();
+        fs::write(format!("{}/private/key.txt", test_dir), b"key").unwrap();
+        fs::write(format!("{}/docs/draft.swp", test_dir), b"draft").unwrap();
+        fs::write(format!("{}/.xilersignore", test_dir), "*.swp\n").unwrap();
+        std::os::unix::fs::symlink("../private/key.txt", format!("{}/docs/key", test_dir)).unwrap();
+        std::os::unix::fs::symlink("draft.swp", format!("{}/docs/draft", test_dir)).unwrap();
+
+        let mut file_system = FileSystem::new(test_dir).unwrap();
+        file_system.set_share_policies(SharePolicies::new(BTreeMap::from([(
+            "private".to_string(),
+            SharePolicy::Hidden,
+        )])));
+        file_system.init_file_node().unwrap();
+
+        // docs는 전송 가능하지만 link가 가리키는 대상은 hidden 혹은 ignore됨
+        assert!(file_system.lookup("docs/key").is_some());
+        assert_eq!(
+            file_system.resolve_request("docs/key"),
+            Err(ServeError::NotDownloadable)
+        );
+        assert_eq!(
+            file_system.resolve_request("docs/draft"),
+            Err(ServeError::NotDownloadable)
+        );
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let mut reply = ok_reply_header().to_vec();
+        reply.extend_from_slice(b"payload");
+        assert_eq!(parse_reply(&reply), Ok(&b"payload"[..]));
+        assert_eq!(parse_reply(b"OK\n"), Ok(&b""[..]));
+
+        let reply = err_reply(&ServeError::NotFound);
+        assert_eq!(parse_reply(&reply), Err(ServeError::NotFound.to_string()));
+        assert!(parse_reply(b"raw bytes").is_err());
+    }
+
+    #[test]
+    fn test_read_request() {
+        let request = request_line("docs/a.txt");
+        assert_eq!(read_request(&request[..]), Ok("docs/a.txt".to_string()));
+        assert_eq!(
+            read_request(&b"docs/a.txt\r\n"[..]),
+            Ok("docs/a.txt".to_string())
+        );
+        // 1024 byte보다 긴 경로도 한 번에 읽음
+        let long_path = format!("{}/a.txt", "d".repeat(2000));
+        assert_eq!(read_request(&request_line(&long_path)[..]), Ok(long_path));
+        // 구분자 없이 쓰기를 종료한 요청
+        assert_eq!(
+            read_request(&b"docs/a.txt"[..]),
+            Ok("docs/a.txt".to_string())
+        );
+
+        let too_long = "d".repeat(MAX_REQUEST_LEN + 1);
+        assert_eq!(
+            read_request(too_long.as_bytes()),
+            Err(ServeError::InvalidPath)
+        );
+        assert_eq!(
+            read_request(&b"\xff\xfe\n"[..]),
+            Err(ServeError::InvalidPath)
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use notify::event::{AccessKind, AccessMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+// 운영체제별 backend(linux: inotify, macos: FSEvents, windows: ReadDirectoryChangesW)는 notify가 선택
+pub struct FsWatcher {
+    root: PathBuf,
+    _watcher: RecommendedWatcher, // drop되면 감시가 중단되므로 보관
+    event_rx: mpsc::Receiver<notify::Result<Event>>,
+}
+
+impl FsWatcher {
+    pub fn new(root: &Path) -> Result<Self, notify::Error> {
+        let (event_tx, event_rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            let _ = event_tx.send(res);
+        })?;
+        watcher.watch(root, RecursiveMode::Recursive)?;
+
+        Ok(FsWatcher {
+            root: root.to_path_buf(),
+            _watcher: watcher,
+            event_rx,
+        })
+    }
+
+    // 첫 이벤트가 올 때까지 대기한 뒤 debounce 동안 이벤트를 모아서,
+    // 다시 구성해야 하는 directory들의 root 기준 상대경로를 반환 (watcher가 종료되면 None)
+    pub fn wait_changes(&self, debounce: Duration) -> Option<Vec<PathBuf>> {
+        let mut changed_paths: Vec<PathBuf> = Vec::new();
+
+        let first_event = self.event_rx.recv().ok()?;
+        self.collect_event(first_event, &mut changed_paths);
+
+        while let Ok(event) = self.event_rx.recv_timeout(debounce) {
+            self.collect_event(event, &mut changed_paths);
+        }
+
+        Some(collapse_dirs(changed_paths))
+    }
+
+    fn collect_event(&self, event: notify::Result<Event>, changed_paths: &mut Vec<PathBuf>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                log::warn!("파일 시스템 감시 중 문제가 발생했습니다: {}", e);
+                return;
+            }
+        };
+
+        match event.kind {
+            EventKind::Access(AccessKind::Close(AccessMode::Write)) => {}
+            EventKind::Access(_) => return, // 내용이 바뀌지 않는 접근은 무시
+            _ => {}
+        }
+
+        for path in event.paths {
+            let relative_path = match path.strip_prefix(&self.root) {
+                Ok(relative_path) => relative_path,
+                Err(_) => continue,
+            };
+
+            // 변경된 entry를 포함하는 directory를 다시 구성 (root 자체가 바뀌면 root)
+            let changed_dir = relative_path.parent().unwrap_or(Path::new(""));
+            changed_paths.push(changed_dir.to_path_buf());
+        }
+    }
+}
+
+// 중복을 제거하고 상위 directory가 이미 포함된 경로는 제외
+fn collapse_dirs(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let sorted: BTreeSet<PathBuf> = paths.into_iter().collect();
+    let mut collapsed: Vec<PathBuf> = Vec::new();
+
+    for path in sorted {
+        if collapsed.iter().any(|dir| path.starts_with(dir)) {
+            continue;
+        }
+        collapsed.push(path);
+    }
+
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse_dirs() {
+        let collapsed = collapse_dirs(vec![
+            PathBuf::from("a/b"),
+            PathBuf::from("a"),
+            PathBuf::from("c/d"),
+            PathBuf::from("a/b/c"),
+            PathBuf::from("c/d"),
+            PathBuf::from("cd"),
+        ]);
+
+        assert_eq!(
+            collapsed,
+            vec![
+                PathBuf::from("a"),
+                PathBuf::from("c/d"),
+                PathBuf::from("cd")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapse_dirs_root() {
+        let collapsed = collapse_dirs(vec![PathBuf::from("a"), PathBuf::from("")]);
+
+        assert_eq!(collapsed, vec![PathBuf::from("")]);
+    }
+}
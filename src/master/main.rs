@@ -2,6 +2,7 @@ use actix_web::rt;
 use std::{sync::mpsc, thread};
 
 mod server;
+use server::config::MasterConfig;
 use server::error_handler::{ErrorHandler, ErrorType, NotAbortError};
 use server::server::Server;
 
@@ -9,7 +10,8 @@ fn main() {
     let _logger_init = server::log::init_logger().unwrap();
     ErrorHandler::create_error_log_dir();
 
-    let server = Server::new("0.0.0.0".to_string(), 8080, "127.0.0.1".to_string(), 27017);
+    let server = Server::new("0.0.0.0".to_string(), 8080, "127.0.0.1".to_string(), 27017)
+        .with_config(MasterConfig::load("config.toml"));
     let worker_num: usize = 4;
 
     loop {
@@ -2,6 +2,8 @@ use std::borrow::BorrowMut;
 use std::sync::Mutex;
 
 use actix_web::{web, HttpResponse, Responder, Result};
+use device::device::namespace::NamespaceView;
+use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::server;
@@ -98,3 +100,84 @@ pub async fn get_device_fs(
 
     Ok(HttpResponse::Ok().body(serialized_fs))
 }
+
+#[derive(Deserialize)]
+pub struct NamespaceQuery {
+    #[serde(default)]
+    view: NamespaceView, // by_device | union
+}
+
+pub async fn get_namespace(
+    data: web::Data<Mutex<server::server::AppState>>,
+    path: web::Path<String>,
+    query: web::Query<NamespaceQuery>,
+) -> Result<impl Responder> {
+    log::debug!("group namespace를 구성합니다. view: {:?}", query.view);
+    let mut data_lock = data.lock().unwrap();
+    let client_group = data_lock.client_group.borrow_mut();
+
+    let manager_uuid = Uuid::parse_str(&path).unwrap();
+
+    let manager = match client_group.get_device_manager(manager_uuid) {
+        Some(manager) => manager,
+        None => {
+            log::warn!("해당하는 manager가 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 manager가 없습니다."));
+        }
+    };
+
+    let serialized_namespace = serde_json::to_string(&manager.namespace(query.view))
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+    Ok(HttpResponse::Ok().body(serialized_namespace))
+}
+
+pub async fn get_stats(
+    data: web::Data<Mutex<server::server::AppState>>,
+    path: web::Path<String>,
+) -> Result<impl Responder> {
+    log::debug!("group 저장 공간 통계를 계산합니다.");
+    let mut data_lock = data.lock().unwrap();
+    let client_group = data_lock.client_group.borrow_mut();
+
+    let manager_uuid = Uuid::parse_str(&path).unwrap();
+
+    let manager = match client_group.get_device_manager(manager_uuid) {
+        Some(manager) => manager,
+        None => {
+            log::warn!("해당하는 manager가 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 manager가 없습니다."));
+        }
+    };
+
+    let serialized_stats = serde_json::to_string(&manager.stats())
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+    Ok(HttpResponse::Ok().body(serialized_stats))
+}
+
+pub async fn get_telemetry(
+    data: web::Data<Mutex<server::server::AppState>>,
+    path: web::Path<String>,
+) -> Result<impl Responder> {
+    let mut data_lock = data.lock().unwrap();
+    let client_group = data_lock.client_group.borrow_mut();
+
+    let manager_uuid = Uuid::parse_str(&path).unwrap();
+
+    let manager = match client_group.get_device_manager(manager_uuid) {
+        Some(manager) => manager,
+        None => {
+            log::warn!("해당하는 manager가 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 manager가 없습니다."));
+        }
+    };
+
+    let serialized_telemetry = serde_json::to_string(&manager.telemetry())
+        .map_err(|e| e.to_string())
+        .unwrap();
+
+    Ok(HttpResponse::Ok().body(serialized_telemetry))
+}
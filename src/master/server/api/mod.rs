@@ -1,3 +1,30 @@
 pub mod delete;
 pub mod get;
+pub mod patch;
 pub mod post;
+pub mod put;
+
+use actix_web::{http::header, HttpRequest, HttpResponse};
+use device::device::codec::{CodecError, Compression, Encoding, WireFormat};
+use serde::de::DeserializeOwned;
+
+// Content-Encoding은 actix-web이 payload를 읽을 때 이미 해제하므로 Content-Type만 확인
+pub fn decode_body<T: DeserializeOwned>(req: &HttpRequest, body: &[u8]) -> Result<T, HttpResponse> {
+    let content_type = req
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok());
+
+    let decoded = Encoding::from_content_type(content_type)
+        .and_then(|encoding| WireFormat::new(encoding, Compression::Identity).decode(body));
+
+    decoded.map_err(|e| {
+        log::warn!("요청 body를 해석하지 못했습니다: {}", e);
+        match e {
+            CodecError::UnsupportedContentType(_) | CodecError::UnsupportedContentEncoding(_) => {
+                HttpResponse::UnsupportedMediaType().body(e.to_string())
+            }
+            _ => HttpResponse::BadRequest().body(e.to_string()),
+        }
+    })
+}
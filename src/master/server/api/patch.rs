@@ -0,0 +1,65 @@
+use std::borrow::BorrowMut;
+use std::sync::Mutex;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Result};
+use device::device::file_sys::SubtreeUpdate;
+use device::device::message::WsCommand;
+use uuid::Uuid;
+
+use crate::server;
+use crate::server::api;
+use crate::server::ws::messages::ClientActorMessage;
+
+pub async fn update_device_fs_subtree(
+    req: HttpRequest,
+    data: web::Data<Mutex<server::server::AppState>>,
+    path: web::Path<(String, String)>,
+    update: web::Bytes, // serialize된 SubtreeUpdate
+) -> Result<impl Responder> {
+    let mut data_lock = data.lock().unwrap();
+    let ws_server = data_lock.ws_server.clone();
+    let client_group = data_lock.client_group.borrow_mut();
+
+    let manager_uuid = Uuid::parse_str(&path.0).unwrap();
+    let fs_uuid = Uuid::parse_str(&path.1).unwrap();
+
+    let manager = match client_group.get_device_manager(manager_uuid) {
+        Some(manager) => manager,
+        None => {
+            log::warn!("해당하는 manager가 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 manager가 없습니다."));
+        }
+    };
+
+    let update: SubtreeUpdate = match api::decode_body(&req, update.as_ref()) {
+        Ok(update) => update,
+        Err(response) => return Ok(response),
+    };
+
+    log::debug!(
+        "device fs의 subtree를 갱신합니다. uuid: {}, path: {:?}",
+        fs_uuid,
+        update.path
+    );
+    let changes = match manager.update_device_fs(fs_uuid, update) {
+        Some(changes) => changes,
+        None => {
+            log::warn!("해당하는 fs 혹은 경로가 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 fs 혹은 경로가 없습니다."));
+        }
+    };
+    log::debug!(
+        "{}개의 변경 사항이 반영되었습니다: {:?}",
+        changes.len(),
+        changes
+    );
+
+    // room에 속한 모든 device에 manager 갱신해야한다는 정보 알림
+    ws_server.do_send(ClientActorMessage {
+        id: fs_uuid,
+        room_id: manager_uuid,
+        msg: WsCommand::Refresh.to_text(),
+    });
+
+    Ok(HttpResponse::Ok().body(fs_uuid.to_string()))
+}
@@ -2,12 +2,48 @@ use std::borrow::BorrowMut;
 use std::sync::Mutex;
 
 use actix_web::{web, HttpRequest, HttpResponse, Responder, Result};
+use device::device::endpoint;
 use device::device::file_sys::FileSystem;
+use device::device::label::LabelError;
+use device::device::message::WsCommand;
+use device::device::protocol::{Capabilities, Feature, HandshakeReply};
 use device::device::spec::DeviceSpec;
 use uuid::Uuid;
 
 use crate::server;
+use crate::server::api;
 use crate::server::device_manager::DeviceManager;
+use crate::server::ws::messages::DeviceMessage;
+
+// client가 group에 참가하기 전에 protocol version과 지원 항목을 확인
+// 호환되지 않으면 426과 함께 사유를 전달하고, 호환되면 master의 capabilities를 전달
+pub async fn handshake(req: HttpRequest, capabilities: web::Bytes) -> Result<impl Responder> {
+    let capabilities: Capabilities = match api::decode_body(&req, capabilities.as_ref()) {
+        Ok(capabilities) => capabilities,
+        Err(response) => return Ok(response),
+    };
+
+    let reply = match capabilities.check_compatible() {
+        Ok(()) => HandshakeReply {
+            accepted: true,
+            capabilities: Capabilities::current(),
+            reason: None,
+        },
+        Err(e) => {
+            log::warn!("호환되지 않는 client의 접속을 거절했습니다: {}", e);
+            HandshakeReply {
+                accepted: false,
+                capabilities: Capabilities::current(),
+                reason: Some(e.to_string()),
+            }
+        }
+    };
+
+    match reply.accepted {
+        true => Ok(HttpResponse::Ok().json(reply)),
+        false => Ok(HttpResponse::UpgradeRequired().json(reply)),
+    }
+}
 
 pub async fn add_device_manager(
     data: web::Data<Mutex<server::server::AppState>>,
@@ -37,6 +73,7 @@ pub async fn add_device_spec(
     spec: web::Bytes, // serialize된 spec
 ) -> Result<impl Responder> {
     let mut data_lock = data.lock().unwrap();
+    let trusted_proxies = data_lock.config.trusted_proxies.clone();
     let client_group = data_lock.client_group.borrow_mut();
 
     let manager_uuid = Uuid::parse_str(&path.0).unwrap();
@@ -48,19 +85,47 @@ pub async fn add_device_spec(
     };
 
     let new_spec_uuid = Uuid::parse_str(&path.1).unwrap();
-    let mut spec: DeviceSpec = serde_json::from_slice(spec.as_ref()).unwrap();
-    spec.ip = req.peer_addr().unwrap().ip().to_string();
+    let mut spec: DeviceSpec = match api::decode_body(&req, spec.as_ref()) {
+        Ok(spec) => spec,
+        Err(response) => return Ok(response),
+    };
+    if let Err(e) = spec.capabilities.check_compatible() {
+        log::warn!(
+            "호환되지 않는 spec을 거절했습니다. uuid: {}, {}",
+            new_spec_uuid,
+            e
+        );
+        return Ok(HttpResponse::UpgradeRequired().body(e.to_string()));
+    }
+    let forwarded_for = req
+        .headers()
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok());
+    let observed_ip = endpoint::observed_ip(
+        req.peer_addr().unwrap().ip(),
+        forwarded_for,
+        &trusted_proxies,
+    );
+    spec.ip = observed_ip.to_string();
+    spec.add_observed_endpoint(observed_ip);
 
-    manager.add_device_spec(new_spec_uuid, spec.clone());
+    match manager.add_device_spec(new_spec_uuid, spec) {
+        Ok(()) => {}
+        Err(e @ LabelError::Duplicate(_)) => {
+            return Ok(HttpResponse::Conflict().body(e.to_string()));
+        }
+        Err(e) => return Ok(HttpResponse::BadRequest().body(e.to_string())),
+    }
     log::debug!("새로운 spec이 추가되었습니다. uuid: {}", new_spec_uuid);
 
     Ok(HttpResponse::Ok().body(new_spec_uuid.to_string()))
 }
 
 pub async fn add_device_fs(
+    req: HttpRequest,
     data: web::Data<Mutex<server::server::AppState>>,
     path: web::Path<(String, String)>,
-    fs: web::Bytes, // serialize된 fs
+    fs: web::Bytes, // serialize된 fs (json 혹은 cbor)
 ) -> Result<impl Responder> {
     let mut data_lock = data.lock().unwrap();
     let client_group = data_lock.client_group.borrow_mut();
@@ -74,10 +139,70 @@ pub async fn add_device_fs(
     };
 
     let new_fs_uuid = Uuid::parse_str(&path.1).unwrap();
-    let fs: FileSystem = serde_json::from_slice(fs.as_ref()).unwrap();
+    let fs: FileSystem = match api::decode_body(&req, fs.as_ref()) {
+        Ok(fs) => fs,
+        Err(response) => return Ok(response),
+    };
 
     manager.add_device_fs(new_fs_uuid, fs.clone());
     log::debug!("새로운 fs가 추가되었습니다. uuid: {}", new_fs_uuid);
 
     Ok(HttpResponse::Ok().body(new_fs_uuid.to_string()))
 }
+
+pub async fn expand_device_fs(
+    data: web::Data<Mutex<server::server::AppState>>,
+    path: web::Path<(String, String)>,
+    subtree_path: String, // root 기준 상대경로
+) -> Result<impl Responder> {
+    let manager_uuid = Uuid::parse_str(&path.0).unwrap();
+    let fs_uuid = Uuid::parse_str(&path.1).unwrap();
+
+    let ws_server = {
+        let mut data_lock = data.lock().unwrap();
+        let ws_server = data_lock.ws_server.clone();
+        let client_group = data_lock.client_group.borrow_mut();
+
+        let manager = match client_group.get_device_manager(manager_uuid) {
+            Some(manager) => manager,
+            None => {
+                log::warn!("해당하는 manager가 없습니다.");
+                return Ok(HttpResponse::NotFound().body("해당하는 manager가 없습니다."));
+            }
+        };
+
+        if manager.get_device_fs(fs_uuid).is_none() {
+            log::warn!("해당하는 fs가 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 fs가 없습니다."));
+        }
+
+        let supports_expand = manager
+            .get_device_spec(fs_uuid)
+            .map(|spec| spec.capabilities.supports(Feature::LazyExpand))
+            .unwrap_or(false);
+        if supports_expand == false {
+            return Ok(HttpResponse::NotImplemented()
+                .body("device가 directory 탐색 요청을 지원하지 않습니다."));
+        }
+
+        ws_server
+    };
+
+    // 결과는 device가 subtree를 전달한 뒤 room 전체에 갱신 알림으로 전달됨
+    log::debug!(
+        "device에 subtree 탐색을 요청합니다. uuid: {}, path: {:?}",
+        fs_uuid,
+        subtree_path
+    );
+    let command = WsCommand::ExpandSubtree { path: subtree_path };
+    match ws_server
+        .send(DeviceMessage {
+            device_id: fs_uuid,
+            msg: command.to_text(),
+        })
+        .await
+    {
+        Ok(true) => Ok(HttpResponse::Accepted().body(fs_uuid.to_string())),
+        _ => Ok(HttpResponse::ServiceUnavailable().body("device가 접속 중이 아닙니다.")),
+    }
+}
@@ -0,0 +1,65 @@
+use std::borrow::BorrowMut;
+use std::sync::Mutex;
+
+use actix_web::{web, HttpRequest, HttpResponse, Responder, Result};
+use device::device::label::{DeviceLabel, LabelError};
+use device::device::message::WsCommand;
+use uuid::Uuid;
+
+use crate::server;
+use crate::server::api;
+use crate::server::ws::messages::ClientActorMessage;
+
+// device의 label과 tag를 변경하고, 정규화되어 반영된 값을 반환
+pub async fn update_device_label(
+    req: HttpRequest,
+    data: web::Data<Mutex<server::server::AppState>>,
+    path: web::Path<(String, String)>,
+    device_label: web::Bytes, // serialize된 DeviceLabel
+) -> Result<impl Responder> {
+    let mut data_lock = data.lock().unwrap();
+    let ws_server = data_lock.ws_server.clone();
+    let client_group = data_lock.client_group.borrow_mut();
+
+    let manager_uuid = Uuid::parse_str(&path.0).unwrap();
+    let spec_uuid = Uuid::parse_str(&path.1).unwrap();
+
+    let manager = match client_group.get_device_manager(manager_uuid) {
+        Some(manager) => manager,
+        None => {
+            log::warn!("해당하는 manager가 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 manager가 없습니다."));
+        }
+    };
+
+    let device_label: DeviceLabel = match api::decode_body(&req, device_label.as_ref()) {
+        Ok(device_label) => device_label,
+        Err(response) => return Ok(response),
+    };
+
+    let device_label = match manager.set_device_label(spec_uuid, device_label) {
+        Some(Ok(device_label)) => device_label,
+        Some(Err(e @ LabelError::Duplicate(_))) => {
+            return Ok(HttpResponse::Conflict().body(e.to_string()));
+        }
+        Some(Err(e)) => return Ok(HttpResponse::BadRequest().body(e.to_string())),
+        None => {
+            log::warn!("해당하는 spec이 없습니다.");
+            return Ok(HttpResponse::NotFound().body("해당하는 spec이 없습니다."));
+        }
+    };
+    log::debug!(
+        "device label이 변경되었습니다. uuid: {}, {:?}",
+        spec_uuid,
+        device_label
+    );
+
+    // 다른 device의 목록과 namespace에 반영되도록 갱신 알림
+    ws_server.do_send(ClientActorMessage {
+        id: spec_uuid,
+        room_id: manager_uuid,
+        msg: WsCommand::Refresh.to_text(),
+    });
+
+    Ok(HttpResponse::Ok().json(device_label))
+}
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+use std::net::IpAddr;
+
+// 압축을 해제한 fs 등록 요청 body의 기본 최대 크기
+const DEFAULT_MAX_PAYLOAD_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct MasterConfig {
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>, // 이 주소에서 온 요청만 X-Forwarded-For로 client 주소를 판단
+    #[serde(default = "default_max_payload_size")]
+    pub max_payload_size: usize, // byte 단위
+}
+
+fn default_max_payload_size() -> usize {
+    DEFAULT_MAX_PAYLOAD_SIZE
+}
+
+impl Default for MasterConfig {
+    fn default() -> Self {
+        MasterConfig {
+            trusted_proxies: Vec::new(),
+            max_payload_size: DEFAULT_MAX_PAYLOAD_SIZE,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    master: MasterConfig,
+}
+
+impl MasterConfig {
+    // client와 같은 config.toml의 [master] 항목, 파일이나 항목이 없으면 기본값
+    pub fn load(path: &str) -> Self {
+        let config_str = match std::fs::read_to_string(path) {
+            Ok(config_str) => config_str,
+            Err(e) => {
+                log::info!("{}을 읽지 못했습니다. 기본 설정을 적용합니다: {}", path, e);
+                return MasterConfig::default();
+            }
+        };
+
+        match toml::from_str::<ConfigFile>(&config_str) {
+            Ok(config_file) => config_file.master,
+            Err(e) => {
+                log::warn!(
+                    "{}의 [master] 항목을 파싱하지 못했습니다. 기본 설정을 적용합니다: {}",
+                    path,
+                    e
+                );
+                MasterConfig::default()
+            }
+        }
+    }
+}
@@ -1,5 +1,10 @@
-use device::device::file_sys::FileSystem;
+use device::device::diff::FsChange;
+use device::device::file_sys::{FileNode, FileSystem, SubtreeUpdate};
+use device::device::label::{self, DeviceLabel, LabelError};
+use device::device::namespace::{self, NamespaceView};
 use device::device::spec::DeviceSpec;
+use device::device::stats::{self, GroupStats};
+use device::device::telemetry::{Telemetry, TelemetryHistory};
 use serde::{Deserialize, Serialize};
 
 use std::collections::BTreeMap;
@@ -11,6 +16,16 @@ pub struct DeviceManager {
     // spec을 가리키는 id와 fs를 가리키는 id가 동일해야 됨 (client의 고유 id)
     id_spec_map: BTreeMap<Uuid, DeviceSpec>,
     id_fs_map: BTreeMap<Uuid, FileSystem>,
+    #[serde(default)]
+    id_telemetry_map: BTreeMap<Uuid, Telemetry>, // 가장 최근 sample, spec과 함께 client에 전달
+    #[serde(skip)]
+    id_telemetry_history: BTreeMap<Uuid, TelemetryHistory>, // telemetry API로만 제공
+}
+
+#[derive(Serialize)]
+pub struct DeviceTelemetry<'a> {
+    pub latest: Option<&'a Telemetry>,
+    pub history: Vec<&'a Telemetry>, // 오래된 순서
 }
 
 impl DeviceManager {
@@ -18,17 +33,84 @@ impl DeviceManager {
         DeviceManager {
             id_spec_map: BTreeMap::new(),
             id_fs_map: BTreeMap::new(),
+            id_telemetry_map: BTreeMap::new(),
+            id_telemetry_history: BTreeMap::new(),
         }
     }
 
-    pub fn add_device_spec(&mut self, id: Uuid, device_spec: DeviceSpec) {
+    // label은 정규화해서 저장하고, group의 다른 device와 겹치면 등록하지 않음
+    pub fn add_device_spec(
+        &mut self,
+        id: Uuid,
+        mut device_spec: DeviceSpec,
+    ) -> Result<(), LabelError> {
+        let device_label = device_spec.device_label().normalized()?;
+        label::check_unique(&self.id_spec_map, &id, &device_label.label)?;
+
+        device_spec.set_device_label(device_label);
         self.id_spec_map.insert(id, device_spec);
+        Ok(())
+    }
+
+    // 등록된 spec이 없으면 None, 반영된 label을 반환
+    pub fn set_device_label(
+        &mut self,
+        id: Uuid,
+        device_label: DeviceLabel,
+    ) -> Option<Result<DeviceLabel, LabelError>> {
+        self.id_spec_map.get(&id)?;
+
+        let result = device_label.normalized().and_then(|device_label| {
+            label::check_unique(&self.id_spec_map, &id, &device_label.label)?;
+            Ok(device_label)
+        });
+        if let Ok(device_label) = &result {
+            self.id_spec_map
+                .get_mut(&id)?
+                .set_device_label(device_label.clone());
+        }
+
+        Some(result)
     }
 
     pub fn add_device_fs(&mut self, id: Uuid, file_system: FileSystem) {
         self.id_fs_map.insert(id, file_system);
     }
 
+    pub fn update_device_fs(&mut self, id: Uuid, update: SubtreeUpdate) -> Option<Vec<FsChange>> {
+        self.id_fs_map.get_mut(&id)?.apply_subtree_update(update)
+    }
+
+    // 등록된 spec이 없는 device의 sample은 무시
+    pub fn record_telemetry(&mut self, id: Uuid, sample: Telemetry) -> bool {
+        if self.id_spec_map.contains_key(&id) == false {
+            return false;
+        }
+
+        self.id_telemetry_history
+            .entry(id)
+            .or_default()
+            .push(sample.clone());
+        self.id_telemetry_map.insert(id, sample);
+        true
+    }
+
+    pub fn telemetry(&self) -> BTreeMap<Uuid, DeviceTelemetry<'_>> {
+        self.id_spec_map
+            .keys()
+            .map(|id| {
+                let history = self.id_telemetry_history.get(id);
+                let device_telemetry = DeviceTelemetry {
+                    latest: self.id_telemetry_map.get(id),
+                    history: history
+                        .map(|history| history.samples().collect())
+                        .unwrap_or_default(),
+                };
+                (*id, device_telemetry)
+            })
+            .collect()
+    }
+
     pub fn get_device_spec(&self, id: Uuid) -> Option<&DeviceSpec> {
         self.id_spec_map.get(&id)
     }
@@ -37,7 +119,19 @@ impl DeviceManager {
         self.id_fs_map.get(&id)
     }
 
+    // device별 저장 공간 통계와 group 전체의 합계
+    pub fn stats(&self) -> GroupStats {
+        stats::group_stats(&self.id_spec_map, &self.id_fs_map)
+    }
+
+    // group 전체의 tree를 하나로 묶은 가상 경로 공간
+    pub fn namespace(&self, view: NamespaceView) -> FileNode {
+        namespace::build_namespace(&self.id_spec_map, &self.id_fs_map, view)
+    }
+
     pub fn delete_device_spec(&mut self, id: Uuid) -> bool {
+        self.id_telemetry_map.remove(&id);
+        self.id_telemetry_history.remove(&id);
         match self.id_spec_map.remove(&id) {
             Some(_) => true,
             None => false,
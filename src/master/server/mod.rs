@@ -1,4 +1,5 @@
 pub mod api;
+pub mod config;
 pub mod db;
 pub mod device_manager;
 pub mod error_handler;
@@ -3,10 +3,11 @@ use std::sync::mpsc;
 use std::sync::Mutex;
 
 use actix::prelude::*;
-use actix_web::{dev::ServerHandle, web, App, HttpServer};
+use actix_web::{dev::ServerHandle, guard, web, App, HttpServer, Scope};
 use uuid::Uuid;
 
 use super::api;
+use super::config::MasterConfig;
 use super::db::MongoDB;
 use super::device_manager::DeviceManager;
 use super::ws::{connection::start_connection, lobby::ClientGroupWs};
@@ -14,6 +15,7 @@ use super::ws::{connection::start_connection, lobby::ClientGroupWs};
 pub struct AppState {
     pub client_group: ClientGroup,
     pub ws_server: Addr<ClientGroupWs>,
+    pub config: MasterConfig,
 }
 
 #[derive(Clone, Debug)]
@@ -60,6 +62,7 @@ pub struct Server {
     db_ip: String,
     db_port: u16,
     pub client_group: ClientGroup,
+    config: MasterConfig,
 }
 
 impl Server {
@@ -72,9 +75,15 @@ impl Server {
             db_ip,
             db_port,
             client_group: ClientGroup::new(),
+            config: MasterConfig::default(),
         }
     }
 
+    pub fn with_config(mut self, config: MasterConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     async fn init_db(&mut self) {
         self.db_client = Some(
             MongoDB::connect_mongodb(self.db_ip.clone(), self.db_port)
@@ -96,10 +105,12 @@ impl Server {
         let app_state = web::Data::new(Mutex::new(AppState {
             client_group: self.client_group.clone(),
             ws_server: ClientGroupWs::new().start(),
+            config: self.config.clone(),
         }));
 
         self.init_db().await;
 
+        let config = self.config.clone();
         let server = HttpServer::new(move || {
             App::new()
                 .app_data(app_state.clone())
@@ -107,45 +118,7 @@ impl Server {
                     web::scope("/ws")
                         .route("/{group_id}/{device_id}", web::get().to(start_connection)),
                 )
-                .service(
-                    web::scope("/api")
-                        .route(
-                            "/device-manager",
-                            web::post().to(api::post::add_device_manager),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}/spec/{device_uuid}",
-                            web::post().to(api::post::add_device_spec),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}/fs/{device_uuid}",
-                            web::post().to(api::post::add_device_fs),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}",
-                            web::get().to(api::get::get_device_manager),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}/spec/{spec_uuid}",
-                            web::get().to(api::get::get_device_spec),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}/fs/{fs_uuid}",
-                            web::get().to(api::get::get_device_fs),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}",
-                            web::delete().to(api::delete::delete_device_manager),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}/spec/{spec_uuid}",
-                            web::delete().to(api::delete::delete_device_spec),
-                        )
-                        .route(
-                            "/device-manager/{manager_uuid}/fs/{fs_uuid}",
-                            web::delete().to(api::delete::delete_device_fs),
-                        ),
-                )
+                .service(api_scope(&config))
         })
         .bind((self.ip.clone(), self.port))?
         .workers(worker_num)
@@ -156,3 +129,180 @@ impl Server {
         server.await
     }
 }
+
+// fs 등록 요청은 tree 전체를 body로 받으므로 기본 제한(256KiB)보다 큰 제한을 적용
+fn api_scope(config: &MasterConfig) -> Scope {
+    let fs_payload_config = web::PayloadConfig::new(config.max_payload_size);
+
+    web::scope("/api")
+        .route("/handshake", web::post().to(api::post::handshake))
+        .route(
+            "/device-manager",
+            web::post().to(api::post::add_device_manager),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/spec/{device_uuid}",
+            web::post().to(api::post::add_device_spec),
+        )
+        .service(
+            web::resource("/device-manager/{manager_uuid}/fs/{device_uuid}")
+                .guard(guard::Post())
+                .app_data(fs_payload_config.clone())
+                .route(web::post().to(api::post::add_device_fs)),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/fs/{device_uuid}/expand",
+            web::post().to(api::post::expand_device_fs),
+        )
+        .service(
+            web::resource("/device-manager/{manager_uuid}/fs/{device_uuid}/subtree")
+                .guard(guard::Patch())
+                .app_data(fs_payload_config)
+                .route(web::patch().to(api::patch::update_device_fs_subtree)),
+        )
+        .route(
+            "/device-manager/{manager_uuid}",
+            web::get().to(api::get::get_device_manager),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/spec/{spec_uuid}",
+            web::get().to(api::get::get_device_spec),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/fs/{fs_uuid}",
+            web::get().to(api::get::get_device_fs),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/namespace",
+            web::get().to(api::get::get_namespace),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/stats",
+            web::get().to(api::get::get_stats),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/telemetry",
+            web::get().to(api::get::get_telemetry),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/spec/{spec_uuid}/label",
+            web::put().to(api::put::update_device_label),
+        )
+        .route(
+            "/device-manager/{manager_uuid}",
+            web::delete().to(api::delete::delete_device_manager),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/spec/{spec_uuid}",
+            web::delete().to(api::delete::delete_device_spec),
+        )
+        .route(
+            "/device-manager/{manager_uuid}/fs/{fs_uuid}",
+            web::delete().to(api::delete::delete_device_fs),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::header, http::StatusCode, test};
+    use device::device::file_sys::FileSystem;
+
+    fn test_app_state(config: &MasterConfig, manager_uuid: Uuid) -> web::Data<Mutex<AppState>> {
+        let mut client_group = ClientGroup::new();
+        client_group
+            .add_device_manager(manager_uuid, DeviceManager::new())
+            .unwrap();
+
+        web::Data::new(Mutex::new(AppState {
+            client_group,
+            ws_server: ClientGroupWs::new().start(),
+            config: config.clone(),
+        }))
+    }
+
+    #[actix_web::test]
+    async fn test_add_large_device_fs() {
+        let test_root = "test_root_master_large_fs";
+        let _ = std::fs::remove_dir_all(test_root);
+        std::fs::create_dir_all(test_root).unwrap();
+        for i in 0..2000 {
+            std::fs::write(format!("{}/{}_{}.txt", test_root, "a".repeat(100), i), "").unwrap();
+        }
+        let mut fs = FileSystem::new(test_root).unwrap();
+        fs.init_file_node().unwrap();
+        let body = serde_json::to_vec(&fs).unwrap();
+        assert!(body.len() > 256 * 1024);
+
+        let manager_uuid = Uuid::new_v4();
+        let device_uuid = Uuid::new_v4();
+        let uri = format!("/api/device-manager/{}/fs/{}", manager_uuid, device_uuid);
+
+        let config = MasterConfig::default();
+        let app = test::init_service(
+            App::new()
+                .app_data(test_app_state(&config, manager_uuid))
+                .service(api_scope(&config)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .set_payload(body.clone())
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 같은 경로의 GET은 제한을 적용한 resource에 가려지지 않음
+        let req = test::TestRequest::get().uri(&uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // 설정한 제한보다 크면 거절
+        let small_config = MasterConfig {
+            max_payload_size: 256 * 1024,
+            ..MasterConfig::default()
+        };
+        let app = test::init_service(
+            App::new()
+                .app_data(test_app_state(&small_config, manager_uuid))
+                .service(api_scope(&small_config)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&uri)
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .set_payload(body)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        std::fs::remove_dir_all(test_root).unwrap();
+    }
+
+    #[actix_web::test]
+    async fn test_add_baseline_device_spec() {
+        let manager_uuid = Uuid::new_v4();
+        let config = MasterConfig::default();
+        let app = test::init_service(
+            App::new()
+                .app_data(test_app_state(&config, manager_uuid))
+                .service(api_scope(&config)),
+        )
+        .await;
+
+        // handshake 이전 client의 spec은 listen_port가 문자열
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/api/device-manager/{}/spec/{}",
+                manager_uuid,
+                Uuid::new_v4()
+            ))
+            .set_payload(r#"{"ip":"","os":"linux","os_version":"6.1","listen_port":"8081"}"#)
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::UPGRADE_REQUIRED);
+    }
+}
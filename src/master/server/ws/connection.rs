@@ -15,7 +15,7 @@ pub async fn start_connection(
 
     let data_lock = data.lock().unwrap();
     let ws_server = data_lock.ws_server.borrow();
-    let ws = WebSocket::new(device_id, group_id, ws_server.clone());
+    let ws = WebSocket::new(device_id, group_id, ws_server.clone(), data.clone());
 
     let resp = ws::start(ws, &req, stream)?;
     Ok(resp)
@@ -1,5 +1,6 @@
-use super::messages::{ClientActorMessage, Connect, Disconnect, WsMessage};
+use super::messages::{ClientActorMessage, Connect, DeviceMessage, Disconnect, WsMessage};
 use actix::prelude::{Actor, Context, Handler, Recipient};
+use device::device::message::WsCommand;
 use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
@@ -73,7 +74,7 @@ impl Handler<Connect> for ClientGroupWs {
             .unwrap()
             .iter()
             .for_each(|client| {
-                self.send_message("", client); // room에 속한 모든 device에 manager 갱신해야한다는 정보 알림
+                self.send_message(&WsCommand::Refresh.to_text(), client); // room에 속한 모든 device에 manager 갱신해야한다는 정보 알림
             });
     }
 }
@@ -82,12 +83,26 @@ impl Handler<ClientActorMessage> for ClientGroupWs {
     type Result = ();
 
     fn handle(&mut self, msg: ClientActorMessage, _ctx: &mut Context<Self>) -> Self::Result {
-        self.rooms
-            .get(&msg.room_id)
-            .unwrap()
-            .iter()
-            .for_each(|client| {
+        match self.rooms.get(&msg.room_id) {
+            Some(room) => room.iter().for_each(|client| {
                 self.send_message(&msg.msg, client);
-            });
+            }),
+            None => log::warn!("{}에 해당하는 group이 존재하지 않습니다.", msg.room_id),
+        }
+    }
+}
+
+impl Handler<DeviceMessage> for ClientGroupWs {
+    type Result = bool;
+
+    // 특정 device에만 전달, 접속 중이 아니면 false
+    fn handle(&mut self, msg: DeviceMessage, _ctx: &mut Context<Self>) -> Self::Result {
+        if self.sessions.contains_key(&msg.device_id) == false {
+            log::warn!("{}에 해당하는 user가 존재하지 않습니다.", msg.device_id);
+            return false;
+        }
+
+        self.send_message(&msg.msg, &msg.device_id);
+        true
     }
 }
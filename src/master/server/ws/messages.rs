@@ -27,3 +27,10 @@ pub struct ClientActorMessage {
     pub room_id: Uuid,
     pub msg: String,
 }
+
+#[derive(Message)]
+#[rtype(result = "bool")]
+pub struct DeviceMessage {
+    pub device_id: Uuid,
+    pub msg: String,
+}
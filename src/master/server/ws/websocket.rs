@@ -1,14 +1,18 @@
 use actix::{fut, ActorContext, ActorFuture, ActorFutureExt, ContextFutureSpawner, WrapFuture};
 use actix::{Actor, Addr, Running, StreamHandler};
 use actix::{AsyncContext, Handler, Recipient};
+use actix_web::web;
 use actix_web_actors::ws;
 use actix_web_actors::ws::Message::Text;
+use device::device::message::WsCommand;
 use std::fmt::Debug;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use super::lobby::ClientGroupWs;
 use super::messages::{ClientActorMessage, Connect, Disconnect, WsMessage};
+use crate::server::server::AppState;
 
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 const CLIENT_TIMEOUT: Duration = Duration::from_secs(10);
@@ -17,16 +21,23 @@ pub struct WebSocket {
     room: Uuid,
     cg_addr: Addr<ClientGroupWs>,
     hb: Instant,
-    id: Uuid, // websocket 이용해 새로 접속하는 device의 uuid
+    id: Uuid,                              // websocket 이용해 새로 접속하는 device의 uuid
+    app_state: web::Data<Mutex<AppState>>, // telemetry를 device manager에 저장
 }
 
 impl WebSocket {
-    pub fn new(device_id: Uuid, room: Uuid, lobby: Addr<ClientGroupWs>) -> Self {
+    pub fn new(
+        device_id: Uuid,
+        room: Uuid,
+        lobby: Addr<ClientGroupWs>,
+        app_state: web::Data<Mutex<AppState>>,
+    ) -> Self {
         WebSocket {
             id: device_id,
             room,
             hb: Instant::now(),
             cg_addr: lobby,
+            app_state,
         }
     }
 }
@@ -78,6 +89,24 @@ impl WebSocket {
             ctx.ping(b"ping");
         });
     }
+
+    fn record_telemetry(&self, text: &str) -> bool {
+        let sample = match WsCommand::from_text(text) {
+            WsCommand::Telemetry { sample } => sample,
+            _ => return false,
+        };
+
+        let mut data_lock = self.app_state.lock().unwrap();
+        match data_lock.client_group.get_device_manager(self.room) {
+            Some(manager) => {
+                if manager.record_telemetry(self.id, sample) == false {
+                    log::warn!("spec이 등록되지 않은 device의 telemetry입니다. {}", self.id);
+                }
+            }
+            None => log::warn!("{}에 해당하는 group이 존재하지 않습니다.", self.room),
+        }
+        true
+    }
 }
 
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WebSocket {
@@ -99,6 +128,8 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for WebSocket {
                 ctx.stop();
             }
             Ok(ws::Message::Nop) => (),
+            // telemetry는 master에만 저장하고 나머지는 group에 전달
+            Ok(Text(s)) if self.record_telemetry(&s) => (),
             Ok(Text(s)) => self.cg_addr.do_send(ClientActorMessage {
                 id: self.id,
                 msg: s.to_string(),